@@ -0,0 +1,298 @@
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
+
+use crate::{
+    rate_limit::{GraphQLCostLimiter, RestCallLimiter},
+    retry::RetryPolicy,
+    Shopify, ShopifyAPIError,
+};
+
+/// Builder for a [`Shopify`] client
+///
+/// The builder owns the configuration of the underlying [`reqwest::Client`], which is
+/// created once and shared by every REST, GraphQL, bulk and staged upload request made
+/// by the resulting client (and its clones).
+/// # Example
+/// ```
+/// use shopify_api::*;
+/// use std::time::Duration;
+///
+/// let shopify = Shopify::builder("myshop", "myapikey", "2024-04")
+///     .timeout(Duration::from_secs(30))
+///     .connect_timeout(Duration::from_secs(5))
+///     .pool_max_idle_per_host(8)
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(shopify.get_shop(), "myshop");
+/// ```
+#[derive(Debug)]
+pub struct ShopifyBuilder {
+    shop: String,
+    api_key: String,
+    api_version: String,
+    #[cfg(feature = "webhooks")]
+    shared_secrets: Vec<String>,
+    #[cfg(feature = "webhooks")]
+    webhook_options: crate::webhooks::handler::WebhookOptions,
+    base_url: Option<String>,
+    client: Option<reqwest::Client>,
+    client_builder: reqwest::ClientBuilder,
+    graphql_limiter: Option<GraphQLCostLimiter>,
+    rest_limiter: Option<RestCallLimiter>,
+    retry_policy: RetryPolicy,
+}
+
+impl ShopifyBuilder {
+    pub(crate) fn new(shop: &str, api_key: &str, api_version: &str) -> ShopifyBuilder {
+        ShopifyBuilder {
+            shop: shop.to_string(),
+            api_key: api_key.to_string(),
+            api_version: api_version.to_string(),
+            #[cfg(feature = "webhooks")]
+            shared_secrets: vec![],
+            #[cfg(feature = "webhooks")]
+            webhook_options: Default::default(),
+            base_url: None,
+            client: None,
+            client_builder: reqwest::Client::builder().user_agent(crate::VERSION),
+            graphql_limiter: None,
+            rest_limiter: None,
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Set the shared secret used to verify webhooks
+    #[cfg(feature = "webhooks")]
+    pub fn shared_secret(mut self, shared_secret: &str) -> ShopifyBuilder {
+        self.shared_secrets = vec![shared_secret.to_string()];
+        self
+    }
+
+    /// Set the shared secrets used to verify webhooks, the current one first
+    ///
+    /// A webhook is valid if it is signed by any of them, which allows rotating the secret of
+    /// an app without rejecting the webhooks signed with the previous one.
+    /// # Example
+    /// ```
+    /// use shopify_api::*;
+    ///
+    /// let shopify = Shopify::builder("myshop", "myapikey", "2024-04")
+    ///     .shared_secrets(["newsharedsecret", "oldsharedsecret"])
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    #[cfg(feature = "webhooks")]
+    pub fn shared_secrets<S: Into<String>>(
+        mut self,
+        shared_secrets: impl IntoIterator<Item = S>,
+    ) -> ShopifyBuilder {
+        self.shared_secrets = shared_secrets.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set a function called with every error of [`Shopify::process_webhook`]
+    ///
+    /// With the `debug` feature, payload errors carry the path of the field which failed.
+    /// # Example
+    /// ```
+    /// use shopify_api::*;
+    /// use shopify_api::webhooks::handler::WebhookError;
+    ///
+    /// let shopify = Shopify::builder("myshop", "myapikey", "2024-04")
+    ///     .shared_secret("mysharedsecret")
+    ///     .webhook_error_hook(|error| match error {
+    ///         WebhookError::InvalidPayload { topic, source } => {
+    ///             log::error!("Unexpected {} payload: {}", topic, source)
+    ///         }
+    ///         error => log::warn!("Rejected webhook: {}", error),
+    ///     })
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    #[cfg(feature = "webhooks")]
+    pub fn webhook_error_hook(
+        mut self,
+        hook: impl Fn(&crate::webhooks::handler::WebhookError) + Send + Sync + 'static,
+    ) -> ShopifyBuilder {
+        self.webhook_options.error_hook =
+            Some(crate::webhooks::handler::WebhookErrorHook::new(hook));
+        self
+    }
+
+    /// Deliver webhooks whose payload does not match their topic as raw JSON in
+    /// `ShopifyWebhook::Other` instead of rejecting them, defaults to `false`
+    ///
+    /// The error is still reported to the error hook.
+    #[cfg(feature = "webhooks")]
+    pub fn webhook_raw_fallback(mut self, raw_fallback: bool) -> ShopifyBuilder {
+        self.webhook_options.raw_fallback = raw_fallback;
+        self
+    }
+
+    /// Set the store used by the webhook handlers to skip the deliveries already processed
+    /// # Example
+    /// ```
+    /// use shopify_api::*;
+    /// use shopify_api::webhooks::dedup::MemoryDedupStore;
+    /// use std::time::Duration;
+    ///
+    /// let shopify = Shopify::builder("myshop", "myapikey", "2024-04")
+    ///     .shared_secret("mysharedsecret")
+    ///     .webhook_dedup_store(MemoryDedupStore::new(Duration::from_secs(24 * 3600)))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    #[cfg(feature = "webhooks")]
+    pub fn webhook_dedup_store(
+        mut self,
+        store: impl crate::webhooks::dedup::WebhookDedupStore + 'static,
+    ) -> ShopifyBuilder {
+        self.webhook_options.dedup_store = Some(std::sync::Arc::new(store));
+        self
+    }
+
+    /// Override the scheme and host of every endpoint, defaults to `https://{shop}.myshopify.com`
+    ///
+    /// This is mostly useful to point the client to a local mock server.
+    /// # Example
+    /// ```
+    /// use shopify_api::*;
+    ///
+    /// let shopify = Shopify::builder("myshop", "myapikey", "2024-04")
+    ///     .base_url("http://127.0.0.1:8080")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(shopify.get_query_url(), "http://127.0.0.1:8080/admin/api/2024-04/graphql.json");
+    /// assert_eq!(shopify.get_api_endpoint("products.json"), "http://127.0.0.1:8080/admin/api/2024-04/products.json");
+    /// ```
+    pub fn base_url(mut self, base_url: &str) -> ShopifyBuilder {
+        self.base_url = Some(base_url.to_string());
+        self
+    }
+
+    /// Use an already configured [`reqwest::Client`]
+    ///
+    /// Every other HTTP option set on this builder is ignored when a client is given.
+    pub fn client(mut self, client: reqwest::Client) -> ShopifyBuilder {
+        self.client = Some(client);
+        self
+    }
+
+    /// Set the total timeout of a request, from connecting until the response body is read
+    pub fn timeout(mut self, timeout: Duration) -> ShopifyBuilder {
+        self.client_builder = self.client_builder.timeout(timeout);
+        self
+    }
+
+    /// Set the timeout for the connect phase of a request
+    pub fn connect_timeout(mut self, timeout: Duration) -> ShopifyBuilder {
+        self.client_builder = self.client_builder.connect_timeout(timeout);
+        self
+    }
+
+    /// Route every request through the given proxy
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> ShopifyBuilder {
+        self.client_builder = self.client_builder.proxy(proxy);
+        self
+    }
+
+    /// Override the `User-Agent` header, defaults to [`crate::VERSION`]
+    pub fn user_agent(mut self, user_agent: &str) -> ShopifyBuilder {
+        self.client_builder = self.client_builder.user_agent(user_agent.to_string());
+        self
+    }
+
+    /// Headers sent with every request
+    pub fn default_headers(mut self, headers: HeaderMap) -> ShopifyBuilder {
+        self.client_builder = self.client_builder.default_headers(headers);
+        self
+    }
+
+    /// Maximum number of idle connections kept per host
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> ShopifyBuilder {
+        self.client_builder = self.client_builder.pool_max_idle_per_host(max);
+        self
+    }
+
+    /// How long an idle connection is kept in the pool, `None` keeps it forever
+    pub fn pool_idle_timeout(mut self, timeout: Option<Duration>) -> ShopifyBuilder {
+        self.client_builder = self.client_builder.pool_idle_timeout(timeout);
+        self
+    }
+
+    /// Share a GraphQL cost limiter with other clients of the same shop
+    ///
+    /// Clones of a [`Shopify`] client always share their limiter, this is only needed when
+    /// several clients are built for the same shop.
+    pub fn graphql_limiter(mut self, limiter: GraphQLCostLimiter) -> ShopifyBuilder {
+        self.graphql_limiter = Some(limiter);
+        self
+    }
+
+    /// Set the REST call limiter, e.g. `RestCallLimiter::new(20.0)` for Shopify Plus stores
+    ///
+    /// Defaults to the limits of the standard plans. As for the GraphQL limiter, the same
+    /// limiter can be given to several clients of the same shop.
+    pub fn rest_limiter(mut self, limiter: RestCallLimiter) -> ShopifyBuilder {
+        self.rest_limiter = Some(limiter);
+        self
+    }
+
+    /// Set the default retry policy of the REST and GraphQL requests
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> ShopifyBuilder {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Build the Shopify client
+    /// # Errors
+    /// This function returns an error if the base URL is not a valid URL or if the HTTP client
+    /// cannot be built (e.g. the TLS backend cannot be initialized)
+    pub fn build(self) -> Result<Shopify, ShopifyAPIError> {
+        let client = match self.client {
+            Some(client) => client,
+            None => self.client_builder.build()?,
+        };
+
+        let base_url = match self.base_url {
+            Some(base_url) => {
+                reqwest::Url::parse(&base_url).map_err(|e| {
+                    ShopifyAPIError::Other(format!("Invalid base URL {}: {}", base_url, e))
+                })?;
+
+                base_url.trim_end_matches('/').to_string()
+            }
+            None => {
+                let mut shop_domain = self.shop.clone();
+                if !shop_domain.ends_with(".myshopify.com") {
+                    shop_domain.push_str(".myshopify.com");
+                }
+
+                format!("https://{}", shop_domain)
+            }
+        };
+
+        let query_url = format!("{}/admin/api/{}/graphql.json", base_url, self.api_version);
+        let rest_url = format!("{}/admin/api/{}/", base_url, self.api_version);
+
+        Ok(Shopify {
+            api_version: self.api_version,
+            #[cfg(feature = "webhooks")]
+            shared_secrets: self.shared_secrets,
+            #[cfg(feature = "webhooks")]
+            webhook_options: self.webhook_options,
+            api_key: self.api_key,
+            base_url,
+            query_url,
+            rest_url,
+            shop: self.shop,
+            client,
+            graphql_limiter: self.graphql_limiter.unwrap_or_default(),
+            rest_limiter: self.rest_limiter.unwrap_or_default(),
+            retry_policy: self.retry_policy,
+        })
+    }
+}
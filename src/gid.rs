@@ -0,0 +1,149 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+use thiserror::Error;
+
+const GID_PREFIX: &str = "gid://shopify/";
+
+/// A Shopify global ID, as used by the GraphQL Admin API (`gid://shopify/Product/123`)
+///
+/// The REST Admin API identifies the same resources by their numeric id, see [`Gid::from_rest_id`]
+/// and [`Gid::rest_id`]. Serialized as its string form.
+/// # Example
+/// ```
+/// use shopify_api::Gid;
+///
+/// let gid: Gid = "gid://shopify/Product/123".parse().unwrap();
+///
+/// assert_eq!(gid.resource_type(), "Product");
+/// assert_eq!(gid.id(), "123");
+/// assert_eq!(gid.rest_id(), Some(123));
+/// assert_eq!(gid, Gid::from_rest_id("Product", 123));
+/// assert_eq!(gid.to_string(), "gid://shopify/Product/123");
+///
+/// assert!("123".parse::<Gid>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Gid {
+    resource_type: String,
+    id: String,
+}
+
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum GidParseError {
+    #[error("Missing gid://shopify/ prefix: {0}")]
+    MissingPrefix(String),
+
+    #[error("Missing resource type: {0}")]
+    MissingResourceType(String),
+
+    #[error("Missing id: {0}")]
+    MissingId(String),
+}
+
+impl Gid {
+    /// Create a global ID from its resource type and id
+    pub fn new(resource_type: &str, id: &str) -> Gid {
+        Gid {
+            resource_type: resource_type.to_string(),
+            id: id.to_string(),
+        }
+    }
+
+    /// Create the global ID of a resource from its REST numeric id
+    /// # Example
+    /// ```
+    /// use shopify_api::Gid;
+    ///
+    /// let gid = Gid::from_rest_id("Order", 450789469);
+    /// assert_eq!(gid.to_string(), "gid://shopify/Order/450789469");
+    /// ```
+    pub fn from_rest_id(resource_type: &str, id: u64) -> Gid {
+        Gid::new(resource_type, &id.to_string())
+    }
+
+    /// Get the resource type, e.g. `Product`
+    pub fn resource_type(&self) -> &str {
+        self.resource_type.as_ref()
+    }
+
+    /// Get the id, which is not always numeric (e.g. `gid://shopify/Cart/c1-abc?key=def`)
+    pub fn id(&self) -> &str {
+        self.id.as_ref()
+    }
+
+    /// Get the numeric id used by the REST Admin API, if the id is numeric
+    /// # Example
+    /// ```
+    /// use shopify_api::Gid;
+    ///
+    /// let gid: Gid = "gid://shopify/LineItem/866550311766439020?line_item_key=abc".parse().unwrap();
+    /// assert_eq!(gid.rest_id(), Some(866550311766439020));
+    ///
+    /// let gid: Gid = "gid://shopify/Cart/c1-abc".parse().unwrap();
+    /// assert_eq!(gid.rest_id(), None);
+    /// ```
+    pub fn rest_id(&self) -> Option<u64> {
+        self.id.split('?').next()?.parse().ok()
+    }
+
+    /// Whether this global ID identifies a resource of the given type
+    pub fn is(&self, resource_type: &str) -> bool {
+        self.resource_type == resource_type
+    }
+}
+
+impl FromStr for Gid {
+    type Err = GidParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let path = s
+            .strip_prefix(GID_PREFIX)
+            .ok_or_else(|| GidParseError::MissingPrefix(s.to_string()))?;
+
+        let (resource_type, id) = path
+            .split_once('/')
+            .ok_or_else(|| GidParseError::MissingId(s.to_string()))?;
+
+        if resource_type.is_empty() {
+            return Err(GidParseError::MissingResourceType(s.to_string()));
+        }
+
+        if id.is_empty() {
+            return Err(GidParseError::MissingId(s.to_string()));
+        }
+
+        Ok(Gid::new(resource_type, id))
+    }
+}
+
+impl fmt::Display for Gid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}/{}", GID_PREFIX, self.resource_type, self.id)
+    }
+}
+
+impl From<Gid> for String {
+    fn from(gid: Gid) -> Self {
+        gid.to_string()
+    }
+}
+
+impl Serialize for Gid {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Gid {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let gid = String::deserialize(deserializer)?;
+
+        gid.parse().map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,223 @@
+use crate::{Shopify, ShopifyAPIError};
+use futures::{stream, Stream, TryStreamExt};
+use std::pin::Pin;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt};
+use tokio_util::io::StreamReader;
+
+type BulkReader = Pin<Box<dyn AsyncBufRead + Send>>;
+
+/// Options of a bulk operation result download
+/// # Example
+/// ```
+/// use shopify_api::graphql::bulk_download::BulkDownloadOptions;
+///
+/// let options = BulkDownloadOptions::default().with_offset(1024);
+/// assert_eq!(options.offset, 1024);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BulkDownloadOptions {
+    /// Byte offset of the JSONL file to resume the download from, see [`BulkRecord::offset`]
+    pub offset: u64,
+    /// Decompress the file as gzip, the offset is then counted on the decompressed data
+    #[cfg(feature = "gzip")]
+    pub gzip: bool,
+}
+
+impl BulkDownloadOptions {
+    pub fn with_offset(mut self, offset: u64) -> BulkDownloadOptions {
+        self.offset = offset;
+        self
+    }
+
+    #[cfg(feature = "gzip")]
+    pub fn with_gzip(mut self, gzip: bool) -> BulkDownloadOptions {
+        self.gzip = gzip;
+        self
+    }
+
+    fn is_gzip(&self) -> bool {
+        #[cfg(feature = "gzip")]
+        return self.gzip;
+
+        #[cfg(not(feature = "gzip"))]
+        false
+    }
+}
+
+/// A record of a bulk operation result
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulkRecord<T> {
+    /// Byte offset right after this record, to resume the download from the next one
+    pub offset: u64,
+    pub data: T,
+}
+
+impl Shopify {
+    /// Stream the records of a bulk operation result, line by line
+    ///
+    /// Unlike `download_bulk`, the file is never fully loaded in memory.
+    /// # Example
+    /// ```no_run
+    /// use shopify_api::*;
+    /// use shopify_api::graphql::bulk_download::BulkDownloadOptions;
+    /// use futures::TryStreamExt;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Product {
+    ///     id: Gid,
+    ///     title: String,
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let shopify = Shopify::new("myshop", "myapikey", String::from("2024-04"), None);
+    ///     let url = "https://storage.googleapis.com/shopify/bulk.jsonl";
+    ///
+    ///     let mut products = std::pin::pin!(
+    ///         shopify.download_bulk_stream::<Product>(url, BulkDownloadOptions::default())
+    ///     );
+    ///
+    ///     while let Some(product) = products.try_next().await.unwrap() {
+    ///         println!("{}: {}", product.id, product.title);
+    ///     }
+    /// }
+    /// ```
+    pub fn download_bulk_stream<'a, T>(
+        &'a self,
+        url: &'a str,
+        options: BulkDownloadOptions,
+    ) -> impl Stream<Item = Result<T, ShopifyAPIError>> + 'a
+    where
+        T: serde::de::DeserializeOwned + 'a,
+    {
+        self.download_bulk_records(url, options)
+            .map_ok(|record| record.data)
+    }
+
+    /// Stream the records of a bulk operation result along with their byte offset
+    ///
+    /// If the download is interrupted, it can be resumed from the offset of the last
+    /// processed record using [`BulkDownloadOptions::with_offset`].
+    /// # Example
+    /// ```no_run
+    /// use shopify_api::*;
+    /// use shopify_api::graphql::bulk_download::BulkDownloadOptions;
+    /// use futures::TryStreamExt;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let shopify = Shopify::new("myshop", "myapikey", String::from("2024-04"), None);
+    ///     let url = "https://storage.googleapis.com/shopify/bulk.jsonl";
+    ///     let mut offset = 0;
+    ///
+    ///     let mut records = std::pin::pin!(shopify.download_bulk_records::<serde_json::Value>(
+    ///         url,
+    ///         BulkDownloadOptions::default().with_offset(offset),
+    ///     ));
+    ///
+    ///     while let Ok(Some(record)) = records.try_next().await {
+    ///         offset = record.offset;
+    ///     }
+    /// }
+    /// ```
+    pub fn download_bulk_records<'a, T>(
+        &'a self,
+        url: &'a str,
+        options: BulkDownloadOptions,
+    ) -> impl Stream<Item = Result<BulkRecord<T>, ShopifyAPIError>> + 'a
+    where
+        T: serde::de::DeserializeOwned + 'a,
+    {
+        let offset = options.offset;
+
+        stream::once(self.open_bulk_reader(url, options))
+            .map_ok(move |reader| read_bulk_records(reader, offset))
+            .try_flatten()
+    }
+
+    /// Open the bulk operation result, positioned at the offset of the options
+    async fn open_bulk_reader(
+        &self,
+        url: &str,
+        options: BulkDownloadOptions,
+    ) -> Result<BulkReader, ShopifyAPIError> {
+        let gzip = options.is_gzip();
+        let mut request = self.client().get(self.resolve_url(url));
+
+        // The offset of a gzip file is counted on the decompressed data, so it cannot be requested
+        if options.offset > 0 && !gzip {
+            request = request.header(reqwest::header::RANGE, format!("bytes={}-", options.offset));
+        }
+
+        let response = request.send().await?;
+        let status = response.status();
+
+        // Resuming at the end of the file
+        if status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE && options.offset > 0 {
+            return Ok(Box::pin(tokio::io::empty()));
+        }
+
+        if !status.is_success() {
+            let request_id = crate::request_id(response.headers());
+            let body = response.text().await.unwrap_or_default();
+
+            return Err(ShopifyAPIError::from_status(status, body, request_id));
+        }
+
+        let ranged = status == reqwest::StatusCode::PARTIAL_CONTENT;
+        let mut reader: BulkReader = Box::pin(StreamReader::new(
+            response.bytes_stream().map_err(std::io::Error::other),
+        ));
+
+        #[cfg(feature = "gzip")]
+        if gzip {
+            reader = Box::pin(tokio::io::BufReader::new(
+                async_compression::tokio::bufread::GzipDecoder::new(reader),
+            ));
+        }
+
+        // The server ignored the range, skip the already downloaded data
+        if !ranged && options.offset > 0 {
+            tokio::io::copy(
+                &mut (&mut reader).take(options.offset),
+                &mut tokio::io::sink(),
+            )
+            .await?;
+        }
+
+        Ok(reader)
+    }
+}
+
+fn read_bulk_records<T>(
+    reader: BulkReader,
+    offset: u64,
+) -> impl Stream<Item = Result<BulkRecord<T>, ShopifyAPIError>>
+where
+    T: serde::de::DeserializeOwned,
+{
+    stream::try_unfold((reader, offset), |(mut reader, mut offset)| async move {
+        let mut line = Vec::new();
+
+        loop {
+            line.clear();
+
+            let read = reader.read_until(b'\n', &mut line).await?;
+            if read == 0 {
+                return Ok(None);
+            }
+
+            offset += read as u64;
+
+            let record = line.trim_ascii();
+            if record.is_empty() {
+                continue;
+            }
+
+            let data = serde_json::from_slice(record)?;
+
+            return Ok(Some((BulkRecord { offset, data }, (reader, offset))));
+        }
+    })
+}
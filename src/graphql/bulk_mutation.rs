@@ -0,0 +1,160 @@
+use super::{
+    bulk_download::BulkDownloadOptions, bulk_poll::BulkPollOptions, bulk_query::ShopifyUserError,
+    read_graphql_errors, read_user_errors, GraphQLError,
+};
+use crate::{utils, Shopify, ShopifyAPIError};
+use futures::TryStreamExt;
+use serde_json::Value;
+
+/// The result of a bulk mutation for one of its inputs
+#[derive(Debug, Clone)]
+pub struct BulkMutationResult<I, T> {
+    pub input: I,
+    /// The mutation payload, `None` if Shopify did not return it, e.g. when the line of this input
+    /// is missing from the result file
+    pub data: Option<T>,
+    /// The `userErrors` of the mutation payload
+    pub user_errors: Vec<ShopifyUserError>,
+    /// The GraphQL errors of this input
+    pub errors: Vec<GraphQLError>,
+}
+
+impl<I, T> BulkMutationResult<I, T> {
+    /// Whether the mutation succeeded for this input
+    pub fn is_ok(&self) -> bool {
+        self.data.is_some() && self.user_errors.is_empty() && self.errors.is_empty()
+    }
+}
+
+/// Get the mutation payload of a bulk mutation result line (`data.<mutation>`)
+fn mutation_payload(record: &Value) -> Option<&Value> {
+    record
+        .get("data")?
+        .as_object()?
+        .values()
+        .next()
+        .filter(|payload| !payload.is_null())
+}
+
+impl Shopify {
+    /// Runs a bulk mutation for every input and pairs the results with their input.
+    ///
+    /// The inputs are uploaded as the JSONL variables of the mutation, then the operation is run,
+    /// awaited with the given `BulkPollOptions` and its results downloaded and matched by `__lineNumber`,
+    /// whatever their order in the result file. An input without a result line is returned with no
+    /// `data`, so it is never reported as successful.
+    /// # Example
+    /// ```no_run
+    /// use shopify_api::*;
+    /// use shopify_api::graphql::bulk_poll::BulkPollOptions;
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize)]
+    /// struct TagsAdd {
+    ///     id: Gid,
+    ///     tags: Vec<String>,
+    /// }
+    ///
+    /// #[derive(Deserialize)]
+    /// struct TagsAddPayload {
+    ///     node: Option<serde_json::Value>,
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let shopify = Shopify::new("myshop", "myapikey", String::from("2024-04"), None);
+    ///     let inputs = vec![TagsAdd {
+    ///         id: Gid::from_rest_id("Product", 1),
+    ///         tags: vec!["sale".to_string()],
+    ///     }];
+    ///
+    ///     let results = shopify
+    ///         .run_bulk_mutation::<_, TagsAddPayload>(
+    ///             r#"mutation tagsAdd($id: ID!, $tags: [String!]!) {
+    ///                 tagsAdd(id: $id, tags: $tags) {
+    ///                     node { id }
+    ///                     userErrors { field message }
+    ///                 }
+    ///             }"#,
+    ///             inputs,
+    ///             BulkPollOptions::default(),
+    ///         )
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     for result in results.iter().filter(|result| !result.is_ok()) {
+    ///         println!("{}: {:?}", result.input.id, result.user_errors);
+    ///     }
+    /// }
+    /// ```
+    /// # Errors
+    /// This function returns an error if the upload, the operation or the download fails. The errors
+    /// of a single input are returned in its `BulkMutationResult`.
+    pub async fn run_bulk_mutation<I, T>(
+        &self,
+        mutation: &str,
+        inputs: impl IntoIterator<Item = I>,
+        poll_options: BulkPollOptions,
+    ) -> Result<Vec<BulkMutationResult<I, T>>, ShopifyAPIError>
+    where
+        I: serde::Serialize,
+        T: serde::de::DeserializeOwned,
+    {
+        let inputs: Vec<I> = inputs.into_iter().collect();
+        let variables = inputs
+            .iter()
+            .map(serde_json::to_value)
+            .collect::<Result<Vec<Value>, _>>()?;
+
+        let staged_upload_path = self.stage_upload_json(variables).await?;
+
+        let bulk_id = self
+            .make_bulk_mutation(mutation, &staged_upload_path)
+            .await?
+            .bulk_operation
+            .and_then(|bulk| bulk.id)
+            .ok_or_else(|| ShopifyAPIError::Other("Bulk mutation not started".to_string()))?;
+
+        let bulk = self
+            .wait_for_bulk_with(&bulk_id, poll_options, |bulk| {
+                log::debug!("Bulk mutation {}: {:?}", bulk_id, bulk.object_count);
+            })
+            .await?;
+
+        let mut results: Vec<BulkMutationResult<I, T>> = inputs
+            .into_iter()
+            .map(|input| BulkMutationResult {
+                input,
+                data: None,
+                user_errors: vec![],
+                errors: vec![],
+            })
+            .collect();
+
+        let url = match bulk.url {
+            Some(url) => url,
+            None => return Ok(results),
+        };
+
+        let mut records = std::pin::pin!(
+            self.download_bulk_stream::<Value>(&url, BulkDownloadOptions::default())
+        );
+
+        while let Some(record) = records.try_next().await? {
+            let result = record
+                .get("__lineNumber")
+                .and_then(Value::as_u64)
+                .and_then(|line_number| results.get_mut(line_number as usize))
+                .ok_or_else(|| ShopifyAPIError::NotWantedJsonFormat(record.to_string()))?;
+
+            result.errors = read_graphql_errors(&record)?;
+
+            if let Some(payload) = mutation_payload(&record) {
+                result.user_errors = read_user_errors(payload)?;
+                result.data = Some(utils::from_json_value(payload)?);
+            }
+        }
+
+        Ok(results)
+    }
+}
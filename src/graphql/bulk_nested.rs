@@ -0,0 +1,251 @@
+use super::bulk_download::BulkDownloadOptions;
+use crate::{utils, Shopify, ShopifyAPIError};
+use futures::{stream, Stream, TryStreamExt};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+/// A top-level record of a bulk query, receiving the records of its nested connections
+///
+/// Bulk query results flatten the nested connections into separate lines linked to their parent
+/// by `__parentId`. Use an untagged enum as `Child` when the query selects several connections.
+/// Deeper records, linked to a child and not to the top-level record, are deserialized as a
+/// `Child` too and given to [`BulkParent::add_nested_child`] of their top-level record.
+/// # Example
+/// ```
+/// use shopify_api::graphql::bulk_nested::{regroup_bulk, BulkParent};
+/// use shopify_api::Gid;
+/// use serde::Deserialize;
+/// use serde_json::json;
+///
+/// #[derive(Deserialize)]
+/// struct Product {
+///     id: Gid,
+///     title: String,
+///     #[serde(skip)]
+///     variants: Vec<Variant>,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Variant {
+///     id: Gid,
+///     sku: String,
+/// }
+///
+/// impl BulkParent for Product {
+///     type Child = Variant;
+///
+///     fn add_child(&mut self, child: Variant) {
+///         self.variants.push(child);
+///     }
+/// }
+///
+/// let records = vec![
+///     json!({ "id": "gid://shopify/Product/1", "title": "Shirt" }),
+///     json!({ "id": "gid://shopify/ProductVariant/2", "sku": "SHIRT-S", "__parentId": "gid://shopify/Product/1" }),
+///     json!({ "id": "gid://shopify/ProductVariant/3", "sku": "SHIRT-M", "__parentId": "gid://shopify/Product/1" }),
+///     json!({ "id": "gid://shopify/Product/4", "title": "Hat" }),
+/// ];
+///
+/// let products: Vec<Product> = regroup_bulk(records).unwrap();
+///
+/// assert_eq!(products.len(), 2);
+/// assert_eq!(products[0].variants.len(), 2);
+/// assert_eq!(products[0].variants[1].sku, "SHIRT-M");
+/// assert!(products[1].variants.is_empty());
+/// ```
+pub trait BulkParent: serde::de::DeserializeOwned {
+    type Child: serde::de::DeserializeOwned;
+
+    fn add_child(&mut self, child: Self::Child);
+
+    /// Receive a record nested deeper than a child, `parent_id` being the `id` of its direct parent
+    ///
+    /// The default implementation adds it with [`BulkParent::add_child`].
+    fn add_nested_child(&mut self, parent_id: &str, child: Self::Child) {
+        let _ = parent_id;
+        self.add_child(child);
+    }
+}
+
+/// Get the `__parentId` of a bulk record, `None` for a top-level record
+fn parent_id(record: &Value) -> Option<&str> {
+    record.get("__parentId").and_then(Value::as_str)
+}
+
+/// Get the `id` of a nested bulk record, `None` for the connections without `id`
+fn child_id(record: &Value) -> Option<&str> {
+    record.get("id").and_then(Value::as_str)
+}
+
+/// Get the `id` of a top-level bulk record
+fn record_id(record: &Value) -> Result<String, ShopifyAPIError> {
+    record
+        .get("id")
+        .and_then(Value::as_str)
+        .map(|id| id.to_string())
+        .ok_or_else(|| ShopifyAPIError::NotWantedJsonFormat(record.to_string()))
+}
+
+fn orphan_error(record: &Value) -> ShopifyAPIError {
+    ShopifyAPIError::Other(format!("Bulk record without parent: {}", record))
+}
+
+/// Regroup the records of a bulk query under their parent, whatever their order
+/// # Errors
+/// This function returns an error if a record cannot be deserialized or if its parent is missing
+pub fn regroup_bulk<P>(records: Vec<Value>) -> Result<Vec<P>, ShopifyAPIError>
+where
+    P: BulkParent,
+{
+    let mut parents: Vec<P> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    let mut children: Vec<Value> = Vec::new();
+
+    for record in records {
+        if parent_id(&record).is_some() {
+            children.push(record);
+            continue;
+        }
+
+        positions.insert(record_id(&record)?, parents.len());
+        parents.push(utils::from_json_value(&record)?);
+    }
+
+    let ancestors: HashMap<&str, &str> = children
+        .iter()
+        .filter_map(|record| Some((child_id(record)?, parent_id(record)?)))
+        .collect();
+
+    for record in &children {
+        let direct_parent = parent_id(record).ok_or_else(|| orphan_error(record))?;
+        let mut root = direct_parent;
+
+        // Walk up the nested records, at most once per record in case of a cycle
+        for _ in 0..children.len() {
+            match ancestors.get(root) {
+                Some(ancestor) => root = ancestor,
+                None => break,
+            }
+        }
+
+        let position = positions.get(root).ok_or_else(|| orphan_error(record))?;
+        let child = utils::from_json_value(record)?;
+
+        match root == direct_parent {
+            true => parents[*position].add_child(child),
+            false => parents[*position].add_nested_child(direct_parent, child),
+        }
+    }
+
+    Ok(parents)
+}
+
+/// Regroup a stream of bulk records under their parent
+///
+/// A parent is yielded once the next top-level record is read, so the children and the deeper
+/// records must follow their parent, as in the files written by Shopify.
+/// # Errors
+/// The stream returns an error if a record cannot be deserialized or if it does not follow its parent
+pub fn regroup_bulk_stream<'a, P, S>(
+    records: S,
+) -> impl Stream<Item = Result<P, ShopifyAPIError>> + 'a
+where
+    P: BulkParent + 'a,
+    S: Stream<Item = Result<Value, ShopifyAPIError>> + 'a,
+{
+    // The current parent, with the ids of the records nested under it
+    let state: (_, Option<(String, P, HashSet<String>)>) = (Box::pin(records), None);
+
+    stream::try_unfold(state, |(mut records, mut current)| async move {
+        while let Some(record) = records.try_next().await? {
+            if let Some(parent_id) = parent_id(&record) {
+                let (id, parent, nested) = match current.as_mut() {
+                    Some((id, parent, nested)) if id == parent_id || nested.contains(parent_id) => {
+                        (id, parent, nested)
+                    }
+                    _ => return Err(orphan_error(&record)),
+                };
+
+                match id == parent_id {
+                    true => parent.add_child(utils::from_json_value(&record)?),
+                    false => parent.add_nested_child(parent_id, utils::from_json_value(&record)?),
+                }
+
+                if let Some(child_id) = child_id(&record) {
+                    nested.insert(child_id.to_string());
+                }
+
+                continue;
+            }
+
+            let next = (
+                record_id(&record)?,
+                utils::from_json_value(&record)?,
+                HashSet::new(),
+            );
+
+            if let Some((_, parent, _)) = current.replace(next) {
+                return Ok(Some((parent, (records, current))));
+            }
+        }
+
+        Ok(current
+            .take()
+            .map(|(_, parent, _)| (parent, (records, current))))
+    })
+}
+
+impl Shopify {
+    /// Stream the records of a bulk query result regrouped under their parent
+    /// # Example
+    /// ```no_run
+    /// use shopify_api::*;
+    /// use shopify_api::graphql::bulk_download::BulkDownloadOptions;
+    /// use shopify_api::graphql::bulk_nested::BulkParent;
+    /// use futures::TryStreamExt;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Order {
+    ///     id: Gid,
+    ///     #[serde(skip)]
+    ///     line_items: Vec<LineItem>,
+    /// }
+    ///
+    /// #[derive(Deserialize)]
+    /// struct LineItem {
+    ///     id: Gid,
+    ///     quantity: u64,
+    /// }
+    ///
+    /// impl BulkParent for Order {
+    ///     type Child = LineItem;
+    ///
+    ///     fn add_child(&mut self, child: LineItem) {
+    ///         self.line_items.push(child);
+    ///     }
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let shopify = Shopify::new("myshop", "myapikey", String::from("2024-04"), None);
+    ///     let url = "https://storage.googleapis.com/shopify/bulk.jsonl";
+    ///
+    ///     let orders: Vec<Order> = shopify
+    ///         .download_bulk_nested(url, BulkDownloadOptions::default())
+    ///         .try_collect()
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub fn download_bulk_nested<'a, P>(
+        &'a self,
+        url: &'a str,
+        options: BulkDownloadOptions,
+    ) -> impl Stream<Item = Result<P, ShopifyAPIError>> + 'a
+    where
+        P: BulkParent + 'a,
+    {
+        regroup_bulk_stream(self.download_bulk_stream::<Value>(url, options))
+    }
+}
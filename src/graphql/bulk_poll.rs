@@ -0,0 +1,216 @@
+use super::bulk_query::{ShopifyBulk, ShopifyBulkStatus};
+use crate::{Gid, Shopify, ShopifyAPIError};
+use futures::{stream, Stream, TryStreamExt};
+use std::time::{Duration, Instant};
+
+/// Polling configuration of a bulk operation
+///
+/// The delay between two polls starts at `interval` and is multiplied by `backoff` after each
+/// poll, up to `max_interval`. Waiting stops with [`ShopifyAPIError::BulkTimedOut`] once
+/// `deadline` is elapsed, 24 hours by default, or never with a `None` deadline.
+/// # Example
+/// ```
+/// use shopify_api::graphql::bulk_poll::BulkPollOptions;
+/// use std::time::Duration;
+///
+/// let options = BulkPollOptions::default()
+///     .with_interval(Duration::from_secs(2))
+///     .with_backoff(2.0)
+///     .with_max_interval(Duration::from_secs(10))
+///     .with_deadline(Some(Duration::from_secs(3600)));
+///
+/// assert_eq!(options.delay(Duration::from_secs(2)), Duration::from_secs(4));
+/// assert_eq!(options.delay(Duration::from_secs(8)), Duration::from_secs(10));
+///
+/// let options = options.with_backoff(f64::INFINITY);
+/// assert_eq!(options.delay(Duration::from_secs(2)), Duration::from_secs(10));
+/// ```
+#[derive(Debug, Clone)]
+pub struct BulkPollOptions {
+    /// Delay before the second poll
+    pub interval: Duration,
+    /// Upper bound of the delay between two polls
+    pub max_interval: Duration,
+    /// Factor applied to the delay after each poll
+    pub backoff: f64,
+    /// Maximum time to wait for the operation to end, `None` to wait forever
+    pub deadline: Option<Duration>,
+}
+
+impl Default for BulkPollOptions {
+    fn default() -> Self {
+        BulkPollOptions {
+            interval: Duration::from_secs(1),
+            max_interval: Duration::from_secs(10),
+            backoff: 1.5,
+            deadline: Some(Duration::from_secs(24 * 3600)),
+        }
+    }
+}
+
+impl BulkPollOptions {
+    pub fn with_interval(mut self, interval: Duration) -> BulkPollOptions {
+        self.interval = interval;
+        self
+    }
+
+    pub fn with_max_interval(mut self, max_interval: Duration) -> BulkPollOptions {
+        self.max_interval = max_interval;
+        self
+    }
+
+    pub fn with_backoff(mut self, backoff: f64) -> BulkPollOptions {
+        self.backoff = backoff;
+        self
+    }
+
+    pub fn with_deadline(mut self, deadline: Option<Duration>) -> BulkPollOptions {
+        self.deadline = deadline;
+        self
+    }
+
+    /// Delay to wait after the given one
+    pub fn delay(&self, previous: Duration) -> Duration {
+        Duration::try_from_secs_f64(previous.as_secs_f64() * self.backoff.max(1.0))
+            .unwrap_or(self.max_interval)
+            .min(self.max_interval)
+    }
+}
+
+struct PollState {
+    started_at: Instant,
+    delay: Option<Duration>,
+    done: bool,
+}
+
+impl Shopify {
+    /// Poll a bulk operation until it ends, yielding a snapshot of every poll
+    ///
+    /// The last snapshot has a final status (`COMPLETED`, `CANCELED`, `FAILED` or `EXPIRED`).
+    /// The stream returns [`ShopifyAPIError::BulkTimedOut`] once the deadline is elapsed.
+    /// # Example
+    /// ```no_run
+    /// use shopify_api::*;
+    /// use shopify_api::graphql::bulk_poll::BulkPollOptions;
+    /// use futures::TryStreamExt;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let shopify = Shopify::new("myshop", "myapikey", String::from("2024-04"), None);
+    ///     let id: Gid = "gid://shopify/BulkOperation/123456".parse().unwrap();
+    ///
+    ///     let mut snapshots = std::pin::pin!(shopify.poll_bulk(&id, BulkPollOptions::default()));
+    ///
+    ///     while let Some(bulk) = snapshots.try_next().await.unwrap() {
+    ///         println!("{:?}: {:?} objects", bulk.status, bulk.object_count);
+    ///     }
+    /// }
+    /// ```
+    pub fn poll_bulk<'a>(
+        &'a self,
+        id: &'a Gid,
+        options: BulkPollOptions,
+    ) -> impl Stream<Item = Result<ShopifyBulk, ShopifyAPIError>> + 'a {
+        let state = PollState {
+            started_at: Instant::now(),
+            delay: None,
+            done: false,
+        };
+
+        stream::try_unfold(state, move |mut state| {
+            let options = options.clone();
+
+            async move {
+                if state.done {
+                    return Ok(None);
+                }
+
+                if let Some(delay) = state.delay {
+                    let delay = match options.deadline {
+                        Some(deadline) => {
+                            let elapsed = state.started_at.elapsed();
+                            if elapsed >= deadline {
+                                return Err(ShopifyAPIError::BulkTimedOut(id.clone()));
+                            }
+
+                            delay.min(deadline - elapsed)
+                        }
+                        None => delay,
+                    };
+
+                    tokio::time::sleep(delay).await;
+                }
+
+                let bulk = self.get_bulk_by_id(id).await?.ok_or_else(|| {
+                    ShopifyAPIError::Other(format!("Bulk operation {} not found", id))
+                })?;
+
+                state.done = bulk.status.is_finished();
+                state.delay = Some(match state.delay {
+                    Some(delay) => options.delay(delay),
+                    None => options.interval,
+                });
+
+                Ok(Some((bulk, state)))
+            }
+        })
+    }
+
+    /// Wait for a bulk operation to end, with the given polling configuration
+    ///
+    /// `progress` is called with every snapshot of the operation.
+    /// # Errors
+    /// This function returns [`ShopifyAPIError::BulkFailed`] or [`ShopifyAPIError::BulkExpired`]
+    /// if the operation failed or expired, and [`ShopifyAPIError::BulkTimedOut`] once the deadline
+    /// is elapsed. A canceled operation is returned as is.
+    /// # Example
+    /// ```no_run
+    /// use shopify_api::*;
+    /// use shopify_api::graphql::bulk_poll::BulkPollOptions;
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let shopify = Shopify::new("myshop", "myapikey", String::from("2024-04"), None);
+    ///     let id: Gid = "gid://shopify/BulkOperation/123456".parse().unwrap();
+    ///     let options = BulkPollOptions::default().with_deadline(Some(Duration::from_secs(600)));
+    ///
+    ///     let bulk = shopify
+    ///         .wait_for_bulk_with(&id, options, |bulk| {
+    ///             println!("{:?} objects", bulk.object_count);
+    ///         })
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub async fn wait_for_bulk_with<F>(
+        &self,
+        id: &Gid,
+        options: BulkPollOptions,
+        mut progress: F,
+    ) -> Result<ShopifyBulk, ShopifyAPIError>
+    where
+        F: FnMut(&ShopifyBulk),
+    {
+        let mut snapshots = std::pin::pin!(self.poll_bulk(id, options));
+        let mut last = None;
+
+        while let Some(bulk) = snapshots.try_next().await? {
+            progress(&bulk);
+            last = Some(bulk);
+        }
+
+        let bulk =
+            last.ok_or_else(|| ShopifyAPIError::Other(format!("Bulk operation {} not found", id)))?;
+
+        match bulk.status {
+            ShopifyBulkStatus::Failed => Err(ShopifyAPIError::BulkFailed {
+                id: id.clone(),
+                error_code: bulk.error_code,
+                partial_data_url: bulk.partial_data_url,
+            }),
+            ShopifyBulkStatus::Expired => Err(ShopifyAPIError::BulkExpired(id.clone())),
+            _ => Ok(bulk),
+        }
+    }
+}
@@ -1,4 +1,8 @@
-use crate::{utils::ReadJsonTreeSteps, Shopify, ShopifyAPIError};
+use super::bulk_download::BulkDownloadOptions;
+use super::bulk_poll::BulkPollOptions;
+use super::types::{DateTime, UnsignedInt64};
+use crate::{utils::ReadJsonTreeSteps, Gid, Shopify, ShopifyAPIError};
+use futures::TryStreamExt;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
@@ -24,6 +28,14 @@ pub enum StagedUploadTargetGenerateUploadResourceInput {
     Video,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum StagedUploadHttpMethod {
+    #[serde(rename = "POST")]
+    Post,
+    #[serde(rename = "PUT")]
+    Put,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct StagedUploadParameter {
     pub name: String,
@@ -34,7 +46,7 @@ pub struct StagedUploadParameter {
 pub struct StagedMediaUploadTarget {
     pub parameters: Vec<StagedUploadParameter>,
     #[serde(rename = "resourceUrl")]
-    pub resource_url: String,
+    pub resource_url: Option<String>,
     pub url: String,
 }
 
@@ -49,11 +61,11 @@ pub struct ShopifyStagedUploadsCreateInputQuery {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct StagedUploadsCreateInput {
     #[serde(rename = "fileSize")]
-    pub file_size: Option<u64>,
+    pub file_size: Option<UnsignedInt64>,
     pub filename: String,
 
-    #[serde(rename = "httpMethod")]
-    pub http_method: Option<String>, // POST OR PUT
+    #[serde(rename = "httpMethod", skip_serializing_if = "Option::is_none")]
+    pub http_method: Option<StagedUploadHttpMethod>,
 
     #[serde(rename = "mimeType")]
     pub mime_type: String,
@@ -61,7 +73,7 @@ pub struct StagedUploadsCreateInput {
     pub resource: StagedUploadTargetGenerateUploadResourceInput,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
 pub enum ShopifyBulkErrorCode {
     #[serde(rename = "ACCESS_DENIED")]
     AccessDenied,
@@ -71,7 +83,7 @@ pub enum ShopifyBulkErrorCode {
     Timeout,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
 pub enum ShopifyBulkStatus {
     #[serde(rename = "CANCELED")]
     Canceled,
@@ -89,21 +101,63 @@ pub enum ShopifyBulkStatus {
     Running,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl ShopifyBulkStatus {
+    /// Whether the bulk operation ended, successfully or not
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            ShopifyBulkStatus::Canceled
+                | ShopifyBulkStatus::Completed
+                | ShopifyBulkStatus::Expired
+                | ShopifyBulkStatus::Failed
+        )
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ShopifyBulk {
-    pub id: Option<String>,
+    pub id: Option<Gid>,
     pub url: Option<String>,
     #[serde(rename = "partialDataUrl")]
     pub partial_data_url: Option<String>,
     pub status: ShopifyBulkStatus,
     #[serde(rename = "errorCode")]
     pub error_code: Option<ShopifyBulkErrorCode>,
+    #[serde(rename = "type")]
+    pub operation_type: Option<ShopifyBulkOperationType>,
+    #[serde(rename = "objectCount")]
+    pub object_count: Option<UnsignedInt64>,
+    #[serde(rename = "rootObjectCount")]
+    pub root_object_count: Option<UnsignedInt64>,
+    #[serde(rename = "fileSize")]
+    pub file_size: Option<UnsignedInt64>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<DateTime>,
+    #[serde(rename = "completedAt")]
+    pub completed_at: Option<DateTime>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct ShopifyUserError {
-    pub field: Vec<String>,
+    pub field: Option<Vec<String>>,
     pub message: String,
+    pub code: Option<String>,
+}
+
+impl std::fmt::Display for ShopifyUserError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(field) = self.field.as_ref().filter(|field| !field.is_empty()) {
+            write!(f, "{}: ", field.join("."))?;
+        }
+
+        write!(f, "{}", self.message)?;
+
+        if let Some(code) = &self.code {
+            write!(f, " ({})", code)?;
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -114,6 +168,47 @@ pub struct ShopifyBulkOperationRunQuery {
     pub user_errors: Option<Vec<ShopifyUserError>>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum ShopifyBulkOperationType {
+    #[serde(rename = "QUERY")]
+    Query,
+    #[serde(rename = "MUTATION")]
+    Mutation,
+}
+
+/// What to do when a bulk operation of the same type is already running on the shop
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ShopifyBulkConflict {
+    /// Return the `userErrors` of Shopify
+    #[default]
+    Fail,
+    /// Wait for the running operation to end, then start the new one
+    Wait,
+    /// Cancel the running operation, then start the new one
+    Cancel,
+}
+
+/// Whether Shopify refused to start a bulk operation because another one is running
+///
+/// The `UserError` of `bulkOperationRunQuery` has no `code`, so the message is
+/// matched when the code is missing.
+fn is_bulk_in_progress(error: &ShopifyAPIError) -> bool {
+    match error {
+        ShopifyAPIError::UserErrors(user_errors) => {
+            user_errors
+                .iter()
+                .any(|user_error| match user_error.code.as_deref() {
+                    Some(code) => code == "OPERATION_IN_PROGRESS",
+                    None => user_error
+                        .message
+                        .to_lowercase()
+                        .contains("already in progress"),
+                })
+        }
+        _ => false,
+    }
+}
+
 impl Shopify {
     /// Query graphql shopify api
     /// # Example
@@ -151,10 +246,9 @@ impl Shopify {
     ///
     ///
     /// ```
-    pub async fn get_bulk_by_id(&self, id: &str) -> Option<ShopifyBulk> {
-        let json: ShopifyBulk = self
-            .graphql_query(
-                r#"
+    pub async fn get_bulk_by_id(&self, id: &Gid) -> Result<Option<ShopifyBulk>, ShopifyAPIError> {
+        self.graphql_query(
+            r#"
                 query($id: ID!) {
                     node(id: $id) {
                         ... on BulkOperation {
@@ -162,20 +256,24 @@ impl Shopify {
                             url
                             partialDataUrl
                             status
+                            errorCode
+                            type
+                            objectCount
+                            rootObjectCount
+                            fileSize
+                            createdAt
+                            completedAt
                         }
                     }
                 }
             "#,
-                &json!({ "id": id }),
-                &vec![
-                    ReadJsonTreeSteps::Key("data"),
-                    ReadJsonTreeSteps::Key("node"),
-                ],
-            )
-            .await
-            .unwrap();
-
-        Some(json)
+            &json!({ "id": id }),
+            &vec![
+                ReadJsonTreeSteps::Key("data"),
+                ReadJsonTreeSteps::Key("node"),
+            ],
+        )
+        .await
     }
     /// Executes a GraphQL bulk query on the Shopify API.
     ///
@@ -217,7 +315,7 @@ impl Shopify {
         );
 
         let result: ShopifyBulkOperationRunQuery = self
-            .graphql_query(
+            .graphql_mutation(
                 &bulk_query,
                 &json!({}),
                 &vec![
@@ -229,6 +327,59 @@ impl Shopify {
 
         Ok(result)
     }
+    /// Executes a GraphQL bulk query, handling an already running bulk query as asked.
+    ///
+    /// # Arguments
+    /// * `query` - The GraphQL query to execute in bulk.
+    /// * `conflict` - What to do if a bulk query is already running.
+    /// * `poll_options` - How to wait for the running bulk query to end, with `Wait` or `Cancel`.
+    ///
+    /// # Returns
+    /// `Result<ShopifyBulkOperationRunQuery, ShopifyAPIError>` - A result containing the bulk operation or an error.
+    ///
+    /// # Example
+    /// ```ts,no_run
+    /// let bulk_query = "{ products { edges { node { id title } } } }";
+    /// let options = BulkPollOptions::default().with_deadline(Some(Duration::from_secs(600)));
+    /// let result = shopify.make_bulk_query_with(bulk_query, ShopifyBulkConflict::Cancel, options).await?;
+    /// ```
+    pub async fn make_bulk_query_with(
+        &self,
+        query: &str,
+        conflict: ShopifyBulkConflict,
+        poll_options: BulkPollOptions,
+    ) -> Result<ShopifyBulkOperationRunQuery, crate::ShopifyAPIError> {
+        let error = match self.make_bulk_query(query).await {
+            Err(error) if conflict != ShopifyBulkConflict::Fail && is_bulk_in_progress(&error) => {
+                error
+            }
+            result => return result,
+        };
+
+        let current = self
+            .current_bulk_operation(ShopifyBulkOperationType::Query)
+            .await?;
+
+        if let Some(id) = current.and_then(|bulk| bulk.id) {
+            if conflict == ShopifyBulkConflict::Cancel {
+                log::debug!("Canceling the running bulk query {}", id);
+                self.cancel_bulk(&id).await?;
+            }
+
+            log::debug!("Waiting for the running bulk query {}", id);
+            match self.wait_for_bulk_with(&id, poll_options, |_| {}).await {
+                Ok(_)
+                | Err(ShopifyAPIError::BulkFailed { .. })
+                | Err(ShopifyAPIError::BulkExpired(_)) => {}
+                Err(error) => return Err(error),
+            }
+        } else {
+            log::debug!("Bulk query refused but none is running: {}", error);
+        }
+
+        self.make_bulk_query(query).await
+    }
+
     /// Executes a bulk mutation on the Shopify API.
     ///
     /// # Arguments
@@ -264,12 +415,13 @@ impl Shopify {
                     userErrors {
                         field
                         message
+                        code
                     }
                 }
             }"#;
 
         let result: ShopifyBulkOperationRunQuery = self
-            .graphql_query(
+            .graphql_mutation(
                 bulk_mutation,
                 &json!({
                     "mutation": mutation_string,
@@ -285,57 +437,105 @@ impl Shopify {
         Ok(result)
     }
 
-    /// Waits for the specified bulk operation to complete.
+    /// Waits for the specified bulk operation to complete, polling it with the default `BulkPollOptions`.
     ///
     /// # Arguments
     /// * `id` - The identifier of the bulk operation.
     ///
     /// # Returns
-    /// `Result<ShopifyBulk, ShopifyAPIError>` - The final status of the bulk operation or an error.
+    /// `Result<ShopifyBulk, ShopifyAPIError>` - The final status of the bulk operation or an error,
+    /// `BulkFailed` or `BulkExpired` if the operation did not complete, `BulkTimedOut` if it did not
+    /// end within the default deadline.
     ///
     /// # Example
     /// ```ts,no_run
-    /// let bulk_id = "123456";
-    /// let bulk_status = shopify.wait_for_bulk(bulk_id);
+    /// let bulk_id: Gid = "gid://shopify/BulkOperation/123456".parse()?;
+    /// let bulk_status = shopify.wait_for_bulk(&bulk_id).await?;
     /// ```
-    pub async fn wait_for_bulk(&self, id: &str) -> Result<ShopifyBulk, crate::ShopifyAPIError> {
-        let mut get_bulk = self.get_bulk_by_id(id).await;
-
-        if get_bulk.is_none() {
-            return Err(crate::ShopifyAPIError::Other(
-                "Bulk operation not found".to_string(),
-            ));
-        }
-
-        let mut bulk = get_bulk.unwrap();
-
-        while bulk.status == ShopifyBulkStatus::Running {
-            get_bulk = self.get_bulk_by_id(id).await;
-
-            if get_bulk.is_none() {
-                return Err(crate::ShopifyAPIError::Other(
-                    "Bulk operation not found".to_string(),
-                ));
-            }
-
-            match get_bulk.as_ref().unwrap().status {
-                ShopifyBulkStatus::Canceled
-                | ShopifyBulkStatus::Completed
-                | ShopifyBulkStatus::Expired
-                | ShopifyBulkStatus::Failed => {
-                    bulk = get_bulk.unwrap();
+    pub async fn wait_for_bulk(&self, id: &Gid) -> Result<ShopifyBulk, crate::ShopifyAPIError> {
+        self.wait_for_bulk_with(id, BulkPollOptions::default(), |_| {})
+            .await
+    }
 
-                    break;
+    /// Gets the bulk operation of the given type currently running on the shop, or the last one.
+    ///
+    /// # Arguments
+    /// * `operation_type` - The type of the bulk operation.
+    ///
+    /// # Returns
+    /// `Result<Option<ShopifyBulk>, ShopifyAPIError>` - The bulk operation, `None` if the shop never ran one.
+    ///
+    /// # Example
+    /// ```ts,no_run
+    /// let current = shopify.current_bulk_operation(ShopifyBulkOperationType::Query).await?;
+    /// ```
+    pub async fn current_bulk_operation(
+        &self,
+        operation_type: ShopifyBulkOperationType,
+    ) -> Result<Option<ShopifyBulk>, ShopifyAPIError> {
+        self.graphql_query(
+            r#"
+            query($type: BulkOperationType!) {
+                currentBulkOperation(type: $type) {
+                    id
+                    url
+                    partialDataUrl
+                    status
+                    errorCode
                 }
-                ShopifyBulkStatus::Canceling
-                | ShopifyBulkStatus::Created
-                | ShopifyBulkStatus::Running => {}
             }
+        "#,
+            &json!({ "type": operation_type }),
+            &vec![
+                ReadJsonTreeSteps::Key("data"),
+                ReadJsonTreeSteps::Key("currentBulkOperation"),
+            ],
+        )
+        .await
+    }
 
-            tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-        }
-
-        Ok(bulk)
+    /// Cancels a running bulk operation.
+    ///
+    /// The operation status is `CANCELING` until Shopify stops it, use `wait_for_bulk` to wait for it.
+    ///
+    /// # Arguments
+    /// * `id` - The identifier of the bulk operation.
+    ///
+    /// # Returns
+    /// `Result<ShopifyBulkOperationRunQuery, ShopifyAPIError>` - A result containing the bulk operation or an error.
+    ///
+    /// # Example
+    /// ```ts,no_run
+    /// let bulk_id: Gid = "gid://shopify/BulkOperation/123456".parse()?;
+    /// let result = shopify.cancel_bulk(&bulk_id).await?;
+    /// ```
+    pub async fn cancel_bulk(
+        &self,
+        id: &Gid,
+    ) -> Result<ShopifyBulkOperationRunQuery, ShopifyAPIError> {
+        self.graphql_mutation(
+            r#"
+            mutation bulkOperationCancel($id: ID!) {
+                bulkOperationCancel(id: $id) {
+                    bulkOperation {
+                        id
+                        url
+                        partialDataUrl
+                        status
+                    }
+                    userErrors {
+                        field
+                        message
+                    }
+                }
+            }"#,
+            &json!({ "id": id }),
+            &vec![
+                ReadJsonTreeSteps::Key("data"),
+                ReadJsonTreeSteps::Key("bulkOperationCancel"),
+            ],
+        )
+        .await
     }
 
     /// Downloads data from a Shopify bulk operation.
@@ -349,16 +549,15 @@ impl Shopify {
     /// # Example
     /// ```ts,no_run
     /// let url = "https://shopify.com/bulk/123456";
-    /// let data = Shopify::download_bulk(url).await?;
+    /// let data = shopify.download_bulk(url).await?;
     /// ```
-    pub async fn download_bulk(url: &str) -> Result<Vec<serde_json::Value>, ShopifyAPIError> {
-        let resp = reqwest::get(url).await?;
-        let body = resp.text().await?;
-
-        body.split('\n')
-            .filter(|line| !line.is_empty())
-            .map(|s| serde_json::from_str(s).map_err(ShopifyAPIError::JsonParseError))
-            .collect::<Result<Vec<serde_json::Value>, _>>()
+    pub async fn download_bulk(
+        &self,
+        url: &str,
+    ) -> Result<Vec<serde_json::Value>, ShopifyAPIError> {
+        self.download_bulk_stream(url, BulkDownloadOptions::default())
+            .try_collect()
+            .await
     }
 
     /// Prepares a staged upload for a bulk operation.
@@ -378,7 +577,7 @@ impl Shopify {
         &self,
         params: Vec<StagedUploadsCreateInput>,
     ) -> Result<ShopifyStagedUploadsCreateInputQuery, ShopifyAPIError> {
-        self.graphql_query(
+        self.graphql_mutation(
             r#"
         mutation stagedUploadsCreate($input: [StagedUploadInput!]!) {
             stagedUploadsCreate(input: $input) {
@@ -431,21 +630,13 @@ impl Shopify {
         let staged_upload_input = StagedUploadsCreateInput {
             file_size: None,
             filename: filename.to_string(),
-            http_method: Some("POST".to_string()),
+            http_method: Some(StagedUploadHttpMethod::Post),
             mime_type: mime_type.to_string(),
             resource: StagedUploadTargetGenerateUploadResourceInput::BulkMutationVariables,
         };
 
         let response = self.stage_upload_prepare(vec![staged_upload_input]).await?;
 
-        if let Some(errors) = response.user_errors {
-            if !errors.is_empty() {
-                return Err(ShopifyAPIError::Other(
-                    "Error while creating staged upload URL".to_string(),
-                ));
-            }
-        }
-
         if let Some(staged_targets) = response.staged_targets {
             if let Some(target) = staged_targets.first() {
                 return Ok(target.clone());
@@ -472,42 +663,23 @@ impl Shopify {
     /// ```
     pub async fn stage_upload_json(&self, data: Vec<Value>) -> Result<String, ShopifyAPIError> {
         let jsonl_data = data
-            .into_iter()
-            .map(|item| serde_json::to_string(&item).unwrap())
-            .collect::<Vec<String>>()
-            .join("\n");
-
-        let upload_url = self
-            .generate_staged_upload_url("bulk_op_vars", "application/jsonl")
-            .await?;
-
-        let mut form = reqwest::multipart::Form::new();
-
-        let mut key = String::from("");
-
-        for param in upload_url.parameters {
-            if param.name == "key" {
-                key = param.value.clone();
-            }
-
-            form = form.text(param.name, param.value);
-        }
-
-        let file_part = reqwest::multipart::Part::bytes(jsonl_data.as_bytes().to_vec())
-            .file_name("bulk_op_vars")
-            .mime_str("application/jsonl")
-            .unwrap();
-
-        form = form.part("file", file_part);
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<Result<Vec<String>, _>>()?
+            .join("\n")
+            .into_bytes();
+
+        let input = StagedUploadsCreateInput {
+            file_size: Some(UnsignedInt64(jsonl_data.len() as u64)),
+            filename: "bulk_op_vars".to_string(),
+            http_method: Some(StagedUploadHttpMethod::Post),
+            mime_type: "application/jsonl".to_string(),
+            resource: StagedUploadTargetGenerateUploadResourceInput::BulkMutationVariables,
+        };
 
-        let client = reqwest::Client::new();
-        client
-            .post(&upload_url.url)
-            .multipart(form)
-            .send()
+        self.stage_upload(input, std::io::Cursor::new(jsonl_data))
             .await?
-            .error_for_status()?;
-
-        Ok(key)
+            .key
+            .ok_or_else(|| ShopifyAPIError::Other("Staged upload key not found".to_string()))
     }
 }
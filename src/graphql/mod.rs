@@ -1,12 +1,127 @@
-mod bulk_query;
+pub mod bulk_download;
+pub mod bulk_mutation;
+pub mod bulk_nested;
+pub mod bulk_poll;
+pub mod bulk_query;
+pub mod pagination;
+pub mod staged_upload;
 pub mod types;
 use crate::{
+    graphql::bulk_query::ShopifyUserError,
+    rate_limit::ShopifyGraphQLCost,
+    retry::RetryPolicy,
     utils::{self, read_json_tree, ReadJsonTreeSteps},
     Shopify, ShopifyAPIError,
 };
 #[cfg(feature = "graphql-client")]
 use graphql_client::{GraphQLQuery, Response as GraphQLResponse};
-use reqwest::{Client, Response};
+use reqwest::Response;
+use serde::{Deserialize, Serialize};
+
+/// A top-level error of a GraphQL response
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct GraphQLError {
+    pub message: String,
+    pub locations: Option<Vec<GraphQLErrorLocation>>,
+    pub path: Option<Vec<serde_json::Value>>,
+    pub extensions: Option<GraphQLErrorExtensions>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct GraphQLErrorLocation {
+    pub line: u64,
+    pub column: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct GraphQLErrorExtensions {
+    pub code: Option<String>,
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl GraphQLError {
+    /// Get the `extensions.code` of the error, e.g. `THROTTLED` or `ACCESS_DENIED`
+    pub fn code(&self) -> Option<&str> {
+        self.extensions.as_ref()?.code.as_deref()
+    }
+}
+
+/// Whether the GraphQL document holds a mutation
+///
+/// Every top-level definition is checked, so a mutation after a fragment is found.
+/// Comments, strings and nested selections are skipped.
+fn is_mutation(graphql_query: &str) -> bool {
+    let mut chars = graphql_query.chars().peekable();
+    let mut depth = 0usize;
+    let mut definition_start = true;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '#' => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '"' => skip_string(&mut chars),
+            '{' | '(' | '[' => depth += 1,
+            '}' | ')' | ']' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 && c == '}' {
+                    definition_start = true;
+                }
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut word = String::from(c);
+                while let Some(&c) = chars.peek() {
+                    if !c.is_alphanumeric() && c != '_' {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+
+                if depth == 0 && definition_start {
+                    if word == "mutation" {
+                        return true;
+                    }
+                    definition_start = false;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    false
+}
+
+/// Skip a GraphQL string or block string, its opening quote already read
+fn skip_string(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) {
+    let mut rest = chars.clone();
+    if rest.next() == Some('"') && rest.next() == Some('"') {
+        chars.next();
+        chars.next();
+        let mut quotes = 0;
+        for c in chars.by_ref() {
+            quotes = if c == '"' { quotes + 1 } else { 0 };
+            if quotes == 3 {
+                return;
+            }
+        }
+        return;
+    }
+
+    let mut escaped = false;
+    for c in chars.by_ref() {
+        match c {
+            '"' if !escaped => return,
+            '\\' if !escaped => escaped = true,
+            _ => escaped = false,
+        }
+    }
+}
 
 async fn shopify_graphql_query<VariablesType, ReturnType>(
     (shopify, graphql_query, variables, json_finder): &(
@@ -21,7 +136,7 @@ where
     ReturnType: serde::de::DeserializeOwned,
 {
     // Prepare the client
-    let client = reqwest::Client::new();
+    let client = shopify.client();
     let mut headers = reqwest::header::HeaderMap::new();
     headers.insert("Content-Type", "application/json".parse().unwrap());
     headers.insert("X-Shopify-Access-Token", shopify.api_key.parse().unwrap());
@@ -30,6 +145,9 @@ where
         "variables": variables
     });
 
+    // Wait for the cost limiter
+    shopify.graphql_limiter.acquire().await;
+
     // Connection Response
     let res: Response = client
         .post(shopify.get_query_url())
@@ -39,6 +157,8 @@ where
         .await?;
 
     // Connection data
+    let status = res.status();
+    let request_id = crate::request_id(res.headers());
     let body = res.text().await;
 
     if body.is_err() {
@@ -46,6 +166,10 @@ where
     }
     let body = body.unwrap();
 
+    if !status.is_success() {
+        return Err(ShopifyAPIError::from_status(status, body, request_id));
+    }
+
     log::debug!(
         "shopify (url: {}) response: {body} \n With body: {}",
         shopify.get_query_url(),
@@ -55,32 +179,110 @@ where
     let json: serde_json::Value =
         serde_json::from_str(&body).map_err(ShopifyAPIError::JsonParseError)?;
 
+    // Feed the cost limiter
+    if let Some(cost) = json.pointer("/extensions/cost") {
+        shopify.update_graphql_cost(cost);
+    }
+
+    let errors = read_graphql_errors(&json)?;
+
     // Check if the query was THROTTLED
-    if let Some(error) = json["errors"]["01"]["extensions"]["code"].as_str() {
-        if error == "THROTTLED" {
-            return Err(ShopifyAPIError::Throttled);
-        }
+    if errors.iter().any(|error| error.code() == Some("THROTTLED")) {
+        return Err(ShopifyAPIError::Throttled { body, request_id });
     }
 
     let json = match read_json_tree(&json, json_finder) {
-        Ok(v) => v,
-        Err(_) => {
-            return Err(ShopifyAPIError::NotWantedJsonFormat(json.to_string()));
+        Ok(v) if errors.is_empty() || !v.is_null() => v,
+        _ if !errors.is_empty() => {
+            return Err(ShopifyAPIError::GraphQLErrors { errors, request_id });
         }
-    };
-
-    let end_json: ReturnType = match serde_json::from_value(json.to_owned()) {
-        Ok(v) => v,
-        Err(_) => {
-            // The shopify response is not wanted json
+        _ => {
             return Err(ShopifyAPIError::NotWantedJsonFormat(json.to_string()));
         }
     };
 
-    Ok(end_json)
+    if !errors.is_empty() {
+        log::warn!(
+            "GraphQL partial response: {}",
+            format_graphql_errors(&errors)
+        );
+    }
+
+    utils::from_json_value(json)
+}
+
+/// Read the top-level `errors` of a GraphQL response
+///
+/// `errors` of an unexpected shape are an error, so they are never mistaken for a success.
+pub(crate) fn read_graphql_errors(
+    json: &serde_json::Value,
+) -> Result<Vec<GraphQLError>, ShopifyAPIError> {
+    match json.get("errors") {
+        Some(serde_json::Value::String(message)) => Ok(vec![GraphQLError {
+            message: message.to_string(),
+            locations: None,
+            path: None,
+            extensions: None,
+        }]),
+        Some(serde_json::Value::Null) | None => Ok(vec![]),
+        Some(errors) => serde_json::from_value(errors.to_owned())
+            .map_err(|_| ShopifyAPIError::NotWantedJsonFormat(errors.to_string())),
+    }
+}
+
+/// Read the `userErrors` of a mutation payload
+/// # Example
+/// ```
+/// use shopify_api::graphql::read_user_errors;
+/// use serde_json::json;
+///
+/// let payload = json!({
+///     "bulkOperation": null,
+///     "userErrors": [{ "field": ["mutation"], "message": "Invalid mutation", "code": "INVALID_MUTATION" }]
+/// });
+///
+/// let user_errors = read_user_errors(&payload).unwrap();
+/// assert_eq!(user_errors[0].to_string(), "mutation: Invalid mutation (INVALID_MUTATION)");
+/// assert!(read_user_errors(&json!({ "userErrors": [] })).unwrap().is_empty());
+/// ```
+/// # Errors
+/// This function returns an error if the `userErrors` are not in the expected format
+pub fn read_user_errors(
+    payload: &serde_json::Value,
+) -> Result<Vec<ShopifyUserError>, ShopifyAPIError> {
+    match payload.get("userErrors") {
+        Some(user_errors) if !user_errors.is_null() => utils::from_json_value(user_errors),
+        _ => Ok(vec![]),
+    }
+}
+
+pub(crate) fn format_user_errors(user_errors: &[ShopifyUserError]) -> String {
+    user_errors
+        .iter()
+        .map(|user_error| user_error.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+pub(crate) fn format_graphql_errors(errors: &[GraphQLError]) -> String {
+    errors
+        .iter()
+        .map(|error| match error.code() {
+            Some(code) => format!("{} ({})", error.message, code),
+            None => error.message.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
 }
 
 impl Shopify {
+    fn update_graphql_cost(&self, cost: &serde_json::Value) {
+        match serde_json::from_value::<ShopifyGraphQLCost>(cost.to_owned()) {
+            Ok(cost) => self.graphql_limiter.update(&cost),
+            Err(e) => log::debug!("Unable to read the GraphQL query cost: {}", e),
+        }
+    }
+
     /// Query graphql shopify api
     /// # Example
     /// ```
@@ -118,19 +320,94 @@ impl Shopify {
         variables: &VariablesType,
         json_finder: &Vec<ReadJsonTreeSteps<'_>>,
     ) -> Result<ReturnType, ShopifyAPIError>
+    where
+        ReturnType: serde::de::DeserializeOwned,
+        VariablesType: serde::Serialize,
+    {
+        self.graphql_query_with_retry(graphql_query, variables, json_finder, self.retry_policy())
+            .await
+    }
+
+    /// Query graphql shopify api with a specific retry policy
+    ///
+    /// Mutations are considered non-idempotent, see [`RetryPolicy`].
+    pub async fn graphql_query_with_retry<ReturnType, VariablesType>(
+        &self,
+        graphql_query: &str,
+        variables: &VariablesType,
+        json_finder: &Vec<ReadJsonTreeSteps<'_>>,
+        retry_policy: &RetryPolicy,
+    ) -> Result<ReturnType, ShopifyAPIError>
     where
         ReturnType: serde::de::DeserializeOwned,
         VariablesType: serde::Serialize,
     {
         let args = (self, graphql_query, variables, json_finder);
-        let response_json = utils::retry_async(
-            10,
-            shopify_graphql_query::<VariablesType, ReturnType>,
-            &args,
-        )
-        .await?;
+        let idempotent = !is_mutation(graphql_query);
+
+        retry_policy
+            .run(idempotent, || {
+                shopify_graphql_query::<VariablesType, ReturnType>(&args)
+            })
+            .await
+    }
+
+    /// Run a graphql mutation, turning its `userErrors` into a `ShopifyAPIError::UserErrors` error
+    ///
+    /// `json_finder` must point to the mutation payload, the one holding the `userErrors` field.
+    /// # Example
+    /// ```no_run
+    /// use shopify_api::*;
+    /// use shopify_api::utils::ReadJsonTreeSteps;
+    /// use serde::Deserialize;
+    /// use serde_json::json;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct TagsAdd {
+    ///     node: Option<serde_json::Value>,
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let shopify = Shopify::new("myshop", "myapikey", String::from("2024-04"), None);
+    ///     let result: Result<TagsAdd, ShopifyAPIError> = shopify
+    ///         .graphql_mutation(
+    ///             r#"mutation($id: ID!, $tags: [String!]!) {
+    ///                 tagsAdd(id: $id, tags: $tags) {
+    ///                     node { id }
+    ///                     userErrors { field message }
+    ///                 }
+    ///             }"#,
+    ///             &json!({ "id": "gid://shopify/Product/1", "tags": ["new"] }),
+    ///             &vec![ReadJsonTreeSteps::Key("data"), ReadJsonTreeSteps::Key("tagsAdd")],
+    ///         )
+    ///         .await;
+    ///
+    ///     if let Err(ShopifyAPIError::UserErrors(user_errors)) = result {
+    ///         println!("{:?}", user_errors);
+    ///     }
+    /// }
+    /// ```
+    pub async fn graphql_mutation<ReturnType, VariablesType>(
+        &self,
+        graphql_mutation: &str,
+        variables: &VariablesType,
+        json_finder: &Vec<ReadJsonTreeSteps<'_>>,
+    ) -> Result<ReturnType, ShopifyAPIError>
+    where
+        ReturnType: serde::de::DeserializeOwned,
+        VariablesType: serde::Serialize,
+    {
+        let payload: serde_json::Value = self
+            .graphql_query(graphql_mutation, variables, json_finder)
+            .await?;
 
-        Ok(response_json)
+        let user_errors = read_user_errors(&payload)?;
+        if !user_errors.is_empty() {
+            return Err(ShopifyAPIError::UserErrors(user_errors));
+        }
+
+        utils::from_json_value(&payload)
     }
 
     // V2 Query using graphql_client
@@ -139,19 +416,27 @@ impl Shopify {
         &self,
         variables: Q::Variables,
     ) -> Result<GraphQLResponse<Q::ResponseData>, reqwest::Error> {
-        let mut headers = reqwest::header::HeaderMap::new();
-        headers.insert("Content-Type", "application/json".parse().unwrap());
-        headers.insert("X-Shopify-Access-Token", self.api_key.parse().unwrap());
-
-        // TODO: put the client in the struct to avoid creating it every time
-        let client = Client::builder()
-            .user_agent(crate::VERSION)
-            .default_headers(headers)
-            .build()?;
+        self.graphql_limiter.acquire().await;
 
         let body = Q::build_query(variables);
-        let reqwest_response = client.post(self.get_query_url()).json(&body).send().await?;
+        let reqwest_response = self
+            .client()
+            .post(self.get_query_url())
+            .header("X-Shopify-Access-Token", &self.api_key)
+            .json(&body)
+            .send()
+            .await?;
+
+        let response: GraphQLResponse<Q::ResponseData> = reqwest_response.json().await?;
+
+        if let Some(cost) = response
+            .extensions
+            .as_ref()
+            .and_then(|extensions| extensions.get("cost"))
+        {
+            self.update_graphql_cost(cost);
+        }
 
-        reqwest_response.json().await
+        Ok(response)
     }
 }
@@ -0,0 +1,165 @@
+use crate::{
+    utils::{self, ReadJsonTreeSteps},
+    Shopify, ShopifyAPIError,
+};
+use futures::{stream, Stream, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct GraphQLPageInfo {
+    #[serde(rename = "hasNextPage")]
+    pub has_next_page: bool,
+    #[serde(rename = "endCursor")]
+    pub end_cursor: Option<String>,
+}
+
+struct PaginationState {
+    variables: serde_json::Map<String, Value>,
+    error: Option<ShopifyAPIError>,
+    after: Option<String>,
+    pages: usize,
+    done: bool,
+}
+
+/// Read the nodes of a connection, from its `nodes` or `edges { node }` field
+fn read_connection_nodes(connection: &Value) -> Result<Vec<Value>, ShopifyAPIError> {
+    if let Some(nodes) = connection.get("nodes").and_then(Value::as_array) {
+        return Ok(nodes.to_owned());
+    }
+
+    if let Some(edges) = connection.get("edges").and_then(Value::as_array) {
+        return edges
+            .iter()
+            .map(|edge| {
+                edge.get("node")
+                    .cloned()
+                    .ok_or_else(|| ShopifyAPIError::NotWantedJsonFormat(edge.to_string()))
+            })
+            .collect();
+    }
+
+    Err(ShopifyAPIError::NotWantedJsonFormat(connection.to_string()))
+}
+
+impl Shopify {
+    /// Iterate over every node of a GraphQL connection, following its cursor
+    ///
+    /// The query must declare an `$after: String` variable given to the connection and select
+    /// `pageInfo { hasNextPage endCursor }` along with `nodes` or `edges { node }`.
+    /// `connection_path` points to the connection in the response. Pages are fetched lazily with
+    /// `graphql_query`, so the rate limiter and the retry policy apply to each of them.
+    /// `variables` must serialize to a JSON object (or `null`), the stream returns an error otherwise.
+    /// # Example
+    /// ```no_run
+    /// use shopify_api::*;
+    /// use shopify_api::utils::ReadJsonTreeSteps;
+    /// use futures::TryStreamExt;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Product {
+    ///     id: String,
+    ///     title: String,
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let shopify = Shopify::new("myshop", "myapikey", String::from("2024-04"), None);
+    ///     let products: Vec<Product> = shopify
+    ///         .graphql_paginate(
+    ///             r#"query($after: String) {
+    ///                 products(first: 250, after: $after) {
+    ///                     nodes { id title }
+    ///                     pageInfo { hasNextPage endCursor }
+    ///                 }
+    ///             }"#,
+    ///             &serde_json::json!({}),
+    ///             vec![ReadJsonTreeSteps::Key("data"), ReadJsonTreeSteps::Key("products")],
+    ///             None,
+    ///         )
+    ///         .try_collect()
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub fn graphql_paginate<'a, NodeType, VariablesType>(
+        &'a self,
+        graphql_query: &'a str,
+        variables: &VariablesType,
+        connection_path: Vec<ReadJsonTreeSteps<'a>>,
+        max_pages: Option<usize>,
+    ) -> impl Stream<Item = Result<NodeType, ShopifyAPIError>> + 'a
+    where
+        NodeType: serde::de::DeserializeOwned + 'a,
+        VariablesType: serde::Serialize,
+    {
+        let (variables, error) = match serde_json::to_value(variables) {
+            Ok(Value::Null) => (Default::default(), None),
+            Ok(Value::Object(variables)) => (variables, None),
+            Ok(variables) => (
+                Default::default(),
+                Some(ShopifyAPIError::Other(format!(
+                    "GraphQL variables must be an object, got {}",
+                    variables
+                ))),
+            ),
+            Err(e) => (Default::default(), Some(ShopifyAPIError::JsonParseError(e))),
+        };
+
+        let state = PaginationState {
+            variables,
+            error,
+            after: None,
+            pages: 0,
+            done: false,
+        };
+
+        stream::try_unfold(state, move |mut state| {
+            let connection_path = connection_path.clone();
+
+            async move {
+                if let Some(error) = state.error.take() {
+                    return Err(error);
+                }
+
+                if state.done || max_pages.is_some_and(|max_pages| state.pages >= max_pages) {
+                    return Ok(None);
+                }
+
+                let mut variables = state.variables.clone();
+                variables.insert(
+                    "after".to_string(),
+                    match &state.after {
+                        Some(after) => Value::String(after.to_string()),
+                        None => Value::Null,
+                    },
+                );
+
+                let connection: Value = self
+                    .graphql_query(graphql_query, &variables, &connection_path)
+                    .await?;
+
+                let page_info: GraphQLPageInfo = match connection.get("pageInfo") {
+                    Some(page_info) => utils::from_json_value(page_info)?,
+                    None => {
+                        return Err(ShopifyAPIError::NotWantedJsonFormat(connection.to_string()))
+                    }
+                };
+
+                let nodes = read_connection_nodes(&connection)?
+                    .iter()
+                    .map(utils::from_json_value)
+                    .collect::<Result<Vec<NodeType>, ShopifyAPIError>>()?;
+
+                state.pages += 1;
+                state.done = !page_info.has_next_page || page_info.end_cursor.is_none();
+                state.after = page_info.end_cursor;
+
+                Ok(Some((nodes, state)))
+            }
+        })
+        .map_ok(|nodes| stream::iter(nodes.into_iter().map(Ok)))
+        .try_flatten()
+    }
+}
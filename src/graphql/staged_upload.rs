@@ -0,0 +1,195 @@
+use super::bulk_query::{
+    StagedMediaUploadTarget, StagedUploadHttpMethod, StagedUploadTargetGenerateUploadResourceInput,
+    StagedUploadsCreateInput,
+};
+use super::types::UnsignedInt64;
+use crate::{Shopify, ShopifyAPIError};
+use std::path::Path;
+use tokio::io::AsyncRead;
+use tokio_util::io::ReaderStream;
+
+/// A file uploaded to a staged upload target
+#[derive(Debug, Clone, PartialEq)]
+pub struct StagedUpload {
+    /// URL of the uploaded file, to give as `originalSource` to `fileCreate` or `productCreateMedia`
+    pub resource_url: Option<String>,
+    /// `key` parameter of the target, to give as `stagedUploadPath` to `bulkOperationRunMutation`
+    pub key: Option<String>,
+}
+
+/// The HTTP method of a target created without `httpMethod`: `POST` for a signed form
+fn upload_method(target: &StagedMediaUploadTarget) -> StagedUploadHttpMethod {
+    let signed_form = target
+        .parameters
+        .iter()
+        .any(|param| param.name == "policy" || param.name == "key");
+
+    match signed_form {
+        true => StagedUploadHttpMethod::Post,
+        false => StagedUploadHttpMethod::Put,
+    }
+}
+
+impl Shopify {
+    /// Uploads a file from disk to a new staged upload target, without loading it in memory.
+    ///
+    /// Shopify chooses the HTTP method of the target, see [`Shopify::stage_upload`].
+    ///
+    /// # Arguments
+    /// * `path` - The path of the file to upload.
+    /// * `mime_type` - The MIME type of the file.
+    /// * `resource` - The resource the file is uploaded for.
+    ///
+    /// # Returns
+    /// `Result<StagedUpload, ShopifyAPIError>` - The uploaded file or an error.
+    ///
+    /// # Example
+    /// ```ts,no_run
+    /// let upload = shopify
+    ///     .stage_upload_file("./cover.png", "image/png", StagedUploadTargetGenerateUploadResourceInput::Image)
+    ///     .await?;
+    /// let original_source = upload.resource_url;
+    /// ```
+    pub async fn stage_upload_file(
+        &self,
+        path: impl AsRef<Path>,
+        mime_type: &str,
+        resource: StagedUploadTargetGenerateUploadResourceInput,
+    ) -> Result<StagedUpload, ShopifyAPIError> {
+        let path = path.as_ref();
+        let file = tokio::fs::File::open(path).await?;
+        let file_size = file.metadata().await?.len();
+
+        let filename = path
+            .file_name()
+            .and_then(|filename| filename.to_str())
+            .ok_or_else(|| ShopifyAPIError::Other(format!("Invalid file name: {:?}", path)))?;
+
+        let input = StagedUploadsCreateInput {
+            file_size: Some(UnsignedInt64(file_size)),
+            filename: filename.to_string(),
+            http_method: None,
+            mime_type: mime_type.to_string(),
+            resource,
+        };
+
+        self.stage_upload(input, file).await
+    }
+
+    /// Uploads the content of a reader to a new staged upload target, without loading it in memory.
+    ///
+    /// Both `POST` (multipart form) and `PUT` targets are supported. The `httpMethod` of the input
+    /// is used when given, otherwise the target is uploaded with `POST` when Shopify returns a
+    /// signed form (`policy` or `key` parameters), as for videos and 3D models, and with `PUT`
+    /// otherwise.
+    /// The `fileSize` of the input is required, as the storage needs the length of the upload.
+    ///
+    /// # Arguments
+    /// * `input` - The description of the file to upload.
+    /// * `reader` - The content of the file.
+    ///
+    /// # Returns
+    /// `Result<StagedUpload, ShopifyAPIError>` - The uploaded file or an error.
+    ///
+    /// # Example
+    /// ```ts,no_run
+    /// let video = tokio::fs::File::open("./demo.mp4").await?;
+    /// let input = StagedUploadsCreateInput {
+    ///     file_size: Some(UnsignedInt64(video.metadata().await?.len())),
+    ///     filename: "demo.mp4".to_string(),
+    ///     http_method: None,
+    ///     mime_type: "video/mp4".to_string(),
+    ///     resource: StagedUploadTargetGenerateUploadResourceInput::Video,
+    /// };
+    /// let upload = shopify.stage_upload(input, video).await?;
+    /// ```
+    pub async fn stage_upload<R>(
+        &self,
+        input: StagedUploadsCreateInput,
+        reader: R,
+    ) -> Result<StagedUpload, ShopifyAPIError>
+    where
+        R: AsyncRead + Send + Sync + 'static,
+    {
+        let file_size = input.file_size.map(u64::from).ok_or_else(|| {
+            ShopifyAPIError::Other("fileSize is required to stage an upload".to_string())
+        })?;
+        let filename = input.filename.to_string();
+        let mime_type = input.mime_type.to_string();
+        let http_method = input.http_method;
+
+        let target: StagedMediaUploadTarget = self
+            .stage_upload_prepare(vec![input])
+            .await?
+            .staged_targets
+            .and_then(|targets| targets.into_iter().next())
+            .ok_or_else(|| {
+                ShopifyAPIError::Other("Unable to generate staged upload URL".to_string())
+            })?;
+
+        let http_method = http_method.unwrap_or_else(|| upload_method(&target));
+
+        let key = target
+            .parameters
+            .iter()
+            .find(|param| param.name == "key")
+            .map(|param| param.value.to_string());
+
+        let body = reqwest::Body::wrap_stream(ReaderStream::new(reader));
+
+        let request = match http_method {
+            StagedUploadHttpMethod::Post => {
+                let mut form = reqwest::multipart::Form::new();
+
+                for param in &target.parameters {
+                    form = form.text(param.name.to_string(), param.value.to_string());
+                }
+
+                let file_part = reqwest::multipart::Part::stream_with_length(body, file_size)
+                    .file_name(filename)
+                    .mime_str(&mime_type)?;
+
+                self.client()
+                    .post(self.resolve_url(&target.url))
+                    .multipart(form.part("file", file_part))
+            }
+            StagedUploadHttpMethod::Put => {
+                let mut request = self
+                    .client()
+                    .put(self.resolve_url(&target.url))
+                    .header(reqwest::header::CONTENT_LENGTH, file_size);
+
+                // The parameters of a PUT target are the headers signed in its URL
+                for param in &target.parameters {
+                    request = match param.name.as_str() {
+                        "content_type" => {
+                            request.header(reqwest::header::CONTENT_TYPE, &param.value)
+                        }
+                        "acl" => request.header("x-goog-acl", &param.value),
+                        name => {
+                            log::debug!("Ignored the staged upload parameter {}", name);
+                            request
+                        }
+                    };
+                }
+
+                request.body(body)
+            }
+        };
+
+        let response = request.send().await?;
+        let status = response.status();
+
+        if !status.is_success() {
+            let request_id = crate::request_id(response.headers());
+            let body = response.text().await.unwrap_or_default();
+
+            return Err(ShopifyAPIError::from_status(status, body, request_id));
+        }
+
+        Ok(StagedUpload {
+            resource_url: target.resource_url,
+            key,
+        })
+    }
+}
@@ -1,12 +1,117 @@
 // Missing types from the GraphQL schema
-#[allow(clippy::upper_case_acronyms)]
-pub type Decimal = String;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
 
+/// Exact decimal number, serialized as a string (`"12.50"`)
 #[allow(clippy::upper_case_acronyms)]
-pub type JSON = String;
+pub type Decimal = rust_decimal::Decimal;
+
+/// Amount of money, serialized as a string (`"12.50"`)
+pub type Money = Decimal;
 
 #[allow(clippy::upper_case_acronyms)]
-pub type UnsignedInt64 = String;
+pub type JSON = serde_json::Value;
 
+/// ISO-8601 date and time, converted to UTC
 #[allow(clippy::upper_case_acronyms)]
-pub type DateTime = String;
+pub type DateTime = chrono::DateTime<chrono::Utc>;
+
+/// An amount of money with its currency, as the `MoneyV2` GraphQL object
+/// # Example
+/// ```
+/// use shopify_api::graphql::types::{Decimal, MoneyV2};
+/// use std::str::FromStr;
+///
+/// let money: MoneyV2 = serde_json::from_str(r#"{"amount": "12.50", "currencyCode": "EUR"}"#).unwrap();
+///
+/// assert_eq!(money.amount, Decimal::from_str("12.5").unwrap());
+/// assert_eq!(money.currency_code, "EUR");
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MoneyV2 {
+    pub amount: Money,
+    #[serde(rename = "currencyCode")]
+    pub currency_code: String,
+}
+
+/// Unsigned 64 bits integer, serialized as a string by Shopify as it exceeds the JSON numbers precision
+/// # Example
+/// ```
+/// use shopify_api::graphql::types::UnsignedInt64;
+///
+/// let size: UnsignedInt64 = serde_json::from_str(r#""18446744073709551615""#).unwrap();
+/// assert_eq!(size, UnsignedInt64(u64::MAX));
+///
+/// let size: UnsignedInt64 = serde_json::from_str("42").unwrap();
+/// assert_eq!(serde_json::to_string(&size).unwrap(), r#""42""#);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct UnsignedInt64(pub u64);
+
+impl From<u64> for UnsignedInt64 {
+    fn from(value: u64) -> Self {
+        UnsignedInt64(value)
+    }
+}
+
+impl From<UnsignedInt64> for u64 {
+    fn from(value: UnsignedInt64) -> Self {
+        value.0
+    }
+}
+
+impl FromStr for UnsignedInt64 {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(UnsignedInt64)
+    }
+}
+
+impl fmt::Display for UnsignedInt64 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Serialize for UnsignedInt64 {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for UnsignedInt64 {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct UnsignedInt64Visitor;
+
+        impl serde::de::Visitor<'_> for UnsignedInt64Visitor {
+            type Value = UnsignedInt64;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an unsigned 64 bits integer or its string representation")
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<Self::Value, E> {
+                Ok(UnsignedInt64(value))
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, value: i64) -> Result<Self::Value, E> {
+                u64::try_from(value)
+                    .map(UnsignedInt64)
+                    .map_err(serde::de::Error::custom)
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                value.parse().map_err(serde::de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_any(UnsignedInt64Visitor)
+    }
+}
@@ -1,20 +1,34 @@
 use thiserror::Error;
 
+pub mod builder;
+pub mod gid;
 pub mod graphql;
+pub mod rate_limit;
 pub mod rest;
+pub mod retry;
 pub mod utils;
 #[cfg(feature = "webhooks")]
 pub mod webhooks;
 
+pub use builder::ShopifyBuilder;
+pub use gid::Gid;
+
 #[derive(Clone, Debug)]
 pub struct Shopify {
     pub api_version: String,
     #[cfg(feature = "webhooks")]
-    shared_secret: Option<String>,
+    shared_secrets: Vec<String>,
+    #[cfg(feature = "webhooks")]
+    webhook_options: webhooks::handler::WebhookOptions,
     api_key: String,
+    base_url: String,
     query_url: String,
     rest_url: String,
     shop: String,
+    client: reqwest::Client,
+    graphql_limiter: rate_limit::GraphQLCostLimiter,
+    rest_limiter: rate_limit::RestCallLimiter,
+    retry_policy: retry::RetryPolicy,
 }
 
 #[derive(Debug, Error)]
@@ -32,15 +46,134 @@ pub enum ShopifyAPIError {
     NotWantedJsonFormat(String),
 
     #[error("Throttled")]
-    Throttled,
+    Throttled {
+        body: String,
+        request_id: Option<String>,
+    },
+
+    #[error("HTTP error {status}: {body}")]
+    HttpError {
+        status: reqwest::StatusCode,
+        body: String,
+        request_id: Option<String>,
+    },
+
+    #[error("Unauthorized: {body}")]
+    Unauthorized {
+        body: String,
+        request_id: Option<String>,
+    },
+
+    #[error("Forbidden: {body}")]
+    Forbidden {
+        body: String,
+        request_id: Option<String>,
+    },
+
+    #[error("Not found: {body}")]
+    NotFound {
+        body: String,
+        request_id: Option<String>,
+    },
+
+    #[error("GraphQL errors: {}", graphql::format_graphql_errors(.errors))]
+    GraphQLErrors {
+        errors: Vec<graphql::GraphQLError>,
+        request_id: Option<String>,
+    },
+
+    #[error("User errors: {}", graphql::format_user_errors(.0))]
+    UserErrors(Vec<graphql::bulk_query::ShopifyUserError>),
+
+    #[error("Deserialization error at {}: {message}", .path.as_deref().unwrap_or("."))]
+    Deserialization {
+        path: Option<String>,
+        message: String,
+        body: String,
+    },
 
     #[error("JSON parsing error: {0}")]
     JsonParseError(#[from] serde_json::Error),
 
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Bulk operation {0} timed out")]
+    BulkTimedOut(Gid),
+
+    #[error("Bulk operation {id} failed: {error_code:?}")]
+    BulkFailed {
+        id: Gid,
+        error_code: Option<graphql::bulk_query::ShopifyBulkErrorCode>,
+        partial_data_url: Option<String>,
+    },
+
+    #[error("Bulk operation {0} expired")]
+    BulkExpired(Gid),
+
     #[error("Other error: {0}")]
     Other(String),
 }
 
+impl ShopifyAPIError {
+    /// Build the error matching a non successful HTTP response
+    pub(crate) fn from_status(
+        status: reqwest::StatusCode,
+        body: String,
+        request_id: Option<String>,
+    ) -> ShopifyAPIError {
+        match status {
+            reqwest::StatusCode::TOO_MANY_REQUESTS => {
+                ShopifyAPIError::Throttled { body, request_id }
+            }
+            reqwest::StatusCode::UNAUTHORIZED => ShopifyAPIError::Unauthorized { body, request_id },
+            reqwest::StatusCode::FORBIDDEN => ShopifyAPIError::Forbidden { body, request_id },
+            reqwest::StatusCode::NOT_FOUND => ShopifyAPIError::NotFound { body, request_id },
+            _ => ShopifyAPIError::HttpError {
+                status,
+                body,
+                request_id,
+            },
+        }
+    }
+
+    /// Get the HTTP status of the response which caused this error, if any
+    pub fn status(&self) -> Option<reqwest::StatusCode> {
+        match self {
+            ShopifyAPIError::ConnectionFailed(e) => e.status(),
+            ShopifyAPIError::Throttled { .. } => Some(reqwest::StatusCode::TOO_MANY_REQUESTS),
+            ShopifyAPIError::HttpError { status, .. } => Some(*status),
+            ShopifyAPIError::Unauthorized { .. } => Some(reqwest::StatusCode::UNAUTHORIZED),
+            ShopifyAPIError::Forbidden { .. } => Some(reqwest::StatusCode::FORBIDDEN),
+            ShopifyAPIError::NotFound { .. } => Some(reqwest::StatusCode::NOT_FOUND),
+            _ => None,
+        }
+    }
+
+    /// Get the `X-Request-Id` of the response which caused this error, if any
+    ///
+    /// This id should be given to the Shopify support when reporting an issue.
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            ShopifyAPIError::Throttled { request_id, .. }
+            | ShopifyAPIError::HttpError { request_id, .. }
+            | ShopifyAPIError::Unauthorized { request_id, .. }
+            | ShopifyAPIError::Forbidden { request_id, .. }
+            | ShopifyAPIError::NotFound { request_id, .. }
+            | ShopifyAPIError::GraphQLErrors { request_id, .. } => request_id.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// Get the `X-Request-Id` header of a response
+pub(crate) fn request_id(headers: &reqwest::header::HeaderMap) -> Option<String> {
+    headers
+        .get("X-Request-Id")
+        .and_then(|request_id| request_id.to_str().ok())
+        .map(|request_id| request_id.to_string())
+}
+
 pub static VERSION: &str = "shopify_api/0.8";
 
 impl Shopify {
@@ -52,35 +185,41 @@ impl Shopify {
     /// // or without shared secret
     /// let shopify = Shopify::new("myshop", "myapikey", String::from("2024-04"), None);
     /// ```
+    /// # Panics
+    /// This function panics if the HTTP client can not be built, e.g. if no TLS backend can be
+    /// initialized. Use [`Shopify::builder`] and [`ShopifyBuilder::build`] to get an error instead.
     pub fn new(
         shop: &str,
         api_key: &str,
         api_version: String,
         #[cfg(feature = "webhooks")] shared_secret: Option<&str>,
     ) -> Shopify {
-        let shop_domain = {
-            let mut shop_domain = shop.to_string();
-            if !shop_domain.ends_with(".myshopify.com") {
-                shop_domain.push_str(".myshopify.com");
-            }
-            shop_domain
+        let builder = Shopify::builder(shop, api_key, &api_version);
+
+        #[cfg(feature = "webhooks")]
+        let builder = match shared_secret {
+            Some(secret) => builder.shared_secret(secret),
+            None => builder,
         };
 
-        let query_url = format!(
-            "https://{}/admin/api/{}/graphql.json",
-            shop_domain, api_version
-        );
-        let rest_url = format!("https://{}/admin/api/{}/", shop_domain, api_version);
-
-        Shopify {
-            api_version,
-            #[cfg(feature = "webhooks")]
-            shared_secret: shared_secret.map(|secret| secret.to_string()),
-            api_key: api_key.to_string(),
-            query_url,
-            rest_url,
-            shop: shop.to_string(),
-        }
+        builder
+            .build()
+            .expect("Failed to build the Shopify HTTP client")
+    }
+
+    /// Create a new Shopify client builder
+    /// # Example
+    /// ```
+    /// use shopify_api::*;
+    /// use std::time::Duration;
+    ///
+    /// let shopify = Shopify::builder("myshop", "myapikey", "2024-04")
+    ///     .timeout(Duration::from_secs(30))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn builder(shop: &str, api_key: &str, api_version: &str) -> ShopifyBuilder {
+        ShopifyBuilder::new(shop, api_key, api_version)
     }
 
     /// Get the shop name
@@ -112,6 +251,60 @@ impl Shopify {
         Ok(self)
     }
 
+    /// Get the HTTP client shared by every request of this Shopify client
+    pub fn client(&self) -> &reqwest::Client {
+        &self.client
+    }
+
+    /// Get the estimated GraphQL throttle status of the shop, `None` until a first GraphQL response is received
+    pub fn graphql_throttle_status(&self) -> Option<rate_limit::ShopifyGraphQLThrottleStatus> {
+        self.graphql_limiter.throttle_status()
+    }
+
+    /// Get the estimated REST call limit of the shop
+    pub fn rest_call_limit(&self) -> rate_limit::ShopifyRestCallLimit {
+        self.rest_limiter.call_limit()
+    }
+
+    /// Get the base url (scheme and host) every endpoint is built from
+    pub fn get_base_url(&self) -> &str {
+        self.base_url.as_ref()
+    }
+
+    /// Resolve a URL returned by Shopify (bulk operation results, staged upload targets, ...)
+    ///
+    /// Absolute URLs are returned as is, relative ones are resolved against the base url.
+    /// # Example
+    /// ```
+    /// use shopify_api::*;
+    ///
+    /// let shopify = Shopify::builder("myshop", "myapikey", "2024-04")
+    ///     .base_url("http://127.0.0.1:8080")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(shopify.resolve_url("/bulk/result.jsonl"), "http://127.0.0.1:8080/bulk/result.jsonl");
+    /// assert_eq!(shopify.resolve_url("https://storage.googleapis.com/result.jsonl"), "https://storage.googleapis.com/result.jsonl");
+    /// ```
+    pub fn resolve_url(&self, url: &str) -> String {
+        if url.starts_with("http://") || url.starts_with("https://") {
+            return url.to_string();
+        }
+
+        format!("{}/{}", self.base_url, url.trim_start_matches('/'))
+    }
+
+    /// Get the retry policy used by `rest_query` and `graphql_query`
+    pub fn retry_policy(&self) -> &retry::RetryPolicy {
+        &self.retry_policy
+    }
+
+    /// Set the retry policy used by `rest_query` and `graphql_query`
+    pub fn set_retry_policy(&mut self, retry_policy: retry::RetryPolicy) -> &mut Shopify {
+        self.retry_policy = retry_policy;
+        self
+    }
+
     /// Get the query url
     pub fn get_query_url(&self) -> &str {
         self.query_url.as_ref()
@@ -133,4 +326,32 @@ impl Shopify {
     pub fn get_api_endpoint(&self, endpoint: &str) -> String {
         format!("{}{}", self.rest_url(), endpoint)
     }
+
+    /// Get the URL of a REST endpoint, which can also be an absolute URL (e.g. a pagination link)
+    ///
+    /// Absolute URLs must have the scheme and host of the shop, as the request carries the
+    /// access token.
+    pub(crate) fn rest_endpoint_url(&self, endpoint: &str) -> Result<String, ShopifyAPIError> {
+        if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+            return Ok(self.get_api_endpoint(endpoint));
+        }
+
+        let url = reqwest::Url::parse(endpoint)
+            .map_err(|e| ShopifyAPIError::Other(format!("Invalid URL {}: {}", endpoint, e)))?;
+        let base_url = reqwest::Url::parse(&self.base_url).map_err(|e| {
+            ShopifyAPIError::Other(format!("Invalid base URL {}: {}", self.base_url, e))
+        })?;
+
+        if url.scheme() != base_url.scheme()
+            || url.host_str() != base_url.host_str()
+            || url.port_or_known_default() != base_url.port_or_known_default()
+        {
+            return Err(ShopifyAPIError::Other(format!(
+                "Refusing to send the access token to {}, which is not on {}",
+                endpoint, self.base_url
+            )));
+        }
+
+        Ok(endpoint.to_string())
+    }
 }
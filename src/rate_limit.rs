@@ -0,0 +1,316 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Longest wait imposed by a limiter, whatever the rates read from the responses
+pub(crate) const MAX_WAIT: Duration = Duration::from_secs(60);
+
+/// Wait for the given number of seconds, capped at [`MAX_WAIT`]
+fn capped_wait(seconds: f64) -> Duration {
+    Duration::try_from_secs_f64(seconds)
+        .unwrap_or(MAX_WAIT)
+        .min(MAX_WAIT)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ShopifyGraphQLThrottleStatus {
+    #[serde(rename = "maximumAvailable")]
+    pub maximum_available: f64,
+    #[serde(rename = "currentlyAvailable")]
+    pub currently_available: f64,
+    #[serde(rename = "restoreRate")]
+    pub restore_rate: f64,
+}
+
+/// The `extensions.cost` object returned with every GraphQL response
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ShopifyGraphQLCost {
+    #[serde(rename = "requestedQueryCost")]
+    pub requested_query_cost: f64,
+    #[serde(rename = "actualQueryCost")]
+    pub actual_query_cost: Option<f64>,
+    #[serde(rename = "throttleStatus")]
+    pub throttle_status: ShopifyGraphQLThrottleStatus,
+}
+
+#[derive(Debug)]
+struct GraphQLBucket {
+    status: Option<ShopifyGraphQLThrottleStatus>,
+    updated_at: Instant,
+    last_requested_cost: f64,
+}
+
+/// Leaky bucket limiter for the GraphQL Admin API, driven by the `extensions.cost` of the responses
+///
+/// Clones share the same bucket, so every clone of a [`crate::Shopify`] client paces its queries
+/// against the same shop budget. Until a first response is received, queries are not delayed.
+/// # Example
+/// ```
+/// use shopify_api::rate_limit::{GraphQLCostLimiter, ShopifyGraphQLCost, ShopifyGraphQLThrottleStatus};
+///
+/// let limiter = GraphQLCostLimiter::new();
+/// assert!(limiter.throttle_status().is_none());
+///
+/// limiter.update(&ShopifyGraphQLCost {
+///     requested_query_cost: 10.0,
+///     actual_query_cost: Some(8.0),
+///     throttle_status: ShopifyGraphQLThrottleStatus {
+///         maximum_available: 2000.0,
+///         currently_available: 1992.0,
+///         restore_rate: 100.0,
+///     },
+/// });
+///
+/// let status = limiter.clone().throttle_status().unwrap();
+/// assert_eq!(status.maximum_available, 2000.0);
+/// assert!(status.currently_available >= 1992.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct GraphQLCostLimiter {
+    bucket: Arc<Mutex<GraphQLBucket>>,
+}
+
+impl Default for GraphQLCostLimiter {
+    fn default() -> Self {
+        GraphQLCostLimiter::new()
+    }
+}
+
+impl GraphQLCostLimiter {
+    pub fn new() -> GraphQLCostLimiter {
+        GraphQLCostLimiter {
+            bucket: Arc::new(Mutex::new(GraphQLBucket {
+                status: None,
+                updated_at: Instant::now(),
+                last_requested_cost: 0.0,
+            })),
+        }
+    }
+
+    /// Get the estimated throttle status, taking into account the points restored since the last response
+    pub fn throttle_status(&self) -> Option<ShopifyGraphQLThrottleStatus> {
+        let bucket = self.bucket.lock().unwrap();
+        let status = bucket.status.as_ref()?;
+
+        Some(ShopifyGraphQLThrottleStatus {
+            currently_available: restored(status, bucket.updated_at.elapsed()),
+            ..status.clone()
+        })
+    }
+
+    /// Update the bucket with the cost returned by Shopify
+    pub fn update(&self, cost: &ShopifyGraphQLCost) {
+        let mut bucket = self.bucket.lock().unwrap();
+
+        bucket.status = Some(cost.throttle_status.clone());
+        bucket.updated_at = Instant::now();
+        bucket.last_requested_cost = cost.requested_query_cost;
+    }
+
+    /// Wait until enough points are available to run a query
+    ///
+    /// The cost of the upcoming query is estimated from the last requested query cost.
+    pub async fn acquire(&self) {
+        let wait = self.reserve();
+
+        if !wait.is_zero() {
+            log::debug!("GraphQL cost limit reached, waiting {:?}", wait);
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Reserve the points of the upcoming query and return how long to wait before sending it
+    fn reserve(&self) -> Duration {
+        let mut bucket = self.bucket.lock().unwrap();
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.updated_at);
+        let last_requested_cost = bucket.last_requested_cost;
+
+        let status = match bucket.status.as_mut() {
+            Some(status) if status.restore_rate > 0.0 => status,
+            _ => return Duration::ZERO,
+        };
+
+        let available = restored(status, elapsed);
+        let cost = last_requested_cost.min(status.maximum_available);
+
+        // The bucket can go below zero, so concurrent queries queue up behind each other
+        status.currently_available = available - cost;
+
+        let wait = if available >= cost {
+            Duration::ZERO
+        } else {
+            capped_wait((cost - available) / status.restore_rate)
+        };
+
+        bucket.updated_at = now;
+
+        wait
+    }
+}
+
+fn restored(status: &ShopifyGraphQLThrottleStatus, elapsed: Duration) -> f64 {
+    (status.currently_available + status.restore_rate * elapsed.as_secs_f64())
+        .min(status.maximum_available)
+}
+
+/// The REST call limit, as sent in the `X-Shopify-Shop-Api-Call-Limit` header
+/// # Example
+/// ```
+/// use shopify_api::rate_limit::ShopifyRestCallLimit;
+///
+/// let limit: ShopifyRestCallLimit = "32/40".parse().unwrap();
+/// assert_eq!(limit, ShopifyRestCallLimit { used: 32, capacity: 40 });
+/// assert!("40".parse::<ShopifyRestCallLimit>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShopifyRestCallLimit {
+    pub used: u32,
+    pub capacity: u32,
+}
+
+impl FromStr for ShopifyRestCallLimit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (used, capacity) = s
+            .trim()
+            .split_once('/')
+            .ok_or_else(|| format!("Invalid call limit: {}", s))?;
+
+        Ok(ShopifyRestCallLimit {
+            used: used
+                .parse()
+                .map_err(|_| format!("Invalid call limit: {}", s))?,
+            capacity: capacity
+                .parse()
+                .map_err(|_| format!("Invalid call limit: {}", s))?,
+        })
+    }
+}
+
+#[derive(Debug)]
+struct RestBucket {
+    used: f64,
+    capacity: f64,
+    updated_at: Instant,
+    retry_at: Option<Instant>,
+}
+
+/// Leaky bucket limiter for the REST Admin API
+///
+/// The bucket is kept in sync with the `X-Shopify-Shop-Api-Call-Limit` header and blocked
+/// for the duration of the `Retry-After` header when a request is throttled. Clones share
+/// the same bucket.
+/// # Example
+/// ```
+/// use shopify_api::rate_limit::{RestCallLimiter, ShopifyRestCallLimit};
+///
+/// // Shopify Plus stores leak 20 requests per second
+/// let limiter = RestCallLimiter::new(20.0);
+/// limiter.update(&ShopifyRestCallLimit { used: 120, capacity: 400 });
+///
+/// let limit = limiter.call_limit();
+/// assert_eq!(limit.capacity, 400);
+/// assert!(limit.used <= 120);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RestCallLimiter {
+    bucket: Arc<Mutex<RestBucket>>,
+    leak_rate: f64,
+}
+
+impl Default for RestCallLimiter {
+    /// Limiter for standard plans: a bucket of 40 requests leaking 2 requests per second
+    fn default() -> Self {
+        RestCallLimiter::new(2.0)
+    }
+}
+
+impl RestCallLimiter {
+    /// Create a limiter leaking `leak_rate` requests per second
+    ///
+    /// The bucket capacity starts at 40 requests and is then read from the responses.
+    pub fn new(leak_rate: f64) -> RestCallLimiter {
+        RestCallLimiter {
+            bucket: Arc::new(Mutex::new(RestBucket {
+                used: 0.0,
+                capacity: 40.0,
+                updated_at: Instant::now(),
+                retry_at: None,
+            })),
+            leak_rate,
+        }
+    }
+
+    /// Get the estimated call limit, taking into account the requests leaked since the last response
+    pub fn call_limit(&self) -> ShopifyRestCallLimit {
+        let bucket = self.bucket.lock().unwrap();
+
+        ShopifyRestCallLimit {
+            used: self.leaked(&bucket, Instant::now()).ceil() as u32,
+            capacity: bucket.capacity as u32,
+        }
+    }
+
+    /// Update the bucket with the call limit returned by Shopify
+    pub fn update(&self, limit: &ShopifyRestCallLimit) {
+        let mut bucket = self.bucket.lock().unwrap();
+
+        bucket.used = limit.used as f64;
+        bucket.capacity = limit.capacity as f64;
+        bucket.updated_at = Instant::now();
+    }
+
+    /// Block every request for the given duration, as asked by a `Retry-After` header
+    pub fn retry_after(&self, duration: Duration) {
+        let mut bucket = self.bucket.lock().unwrap();
+        let now = Instant::now();
+
+        bucket.used = bucket.capacity;
+        bucket.updated_at = now;
+        bucket.retry_at = Some(now + duration);
+    }
+
+    /// Wait until a request can be sent without exceeding the call limit
+    pub async fn acquire(&self) {
+        let wait = self.reserve();
+
+        if !wait.is_zero() {
+            log::debug!("REST call limit reached, waiting {:?}", wait);
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Reserve a request in the bucket and return how long to wait before sending it
+    fn reserve(&self) -> Duration {
+        let mut bucket = self.bucket.lock().unwrap();
+        let now = Instant::now();
+
+        let mut wait = match bucket.retry_at {
+            Some(retry_at) if retry_at > now => retry_at - now,
+            _ => {
+                bucket.retry_at = None;
+                Duration::ZERO
+            }
+        };
+
+        let used = self.leaked(&bucket, now);
+        if used + 1.0 > bucket.capacity && self.leak_rate > 0.0 {
+            wait = wait.max(capped_wait((used + 1.0 - bucket.capacity) / self.leak_rate));
+        }
+
+        // The bucket can go over its capacity, so concurrent requests queue up behind each other
+        bucket.used = used + 1.0;
+        bucket.updated_at = now;
+
+        wait
+    }
+
+    fn leaked(&self, bucket: &RestBucket, now: Instant) -> f64 {
+        let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+
+        (bucket.used - self.leak_rate * elapsed).max(0.0)
+    }
+}
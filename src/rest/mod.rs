@@ -1,10 +1,20 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
+
+use futures::{stream, Stream, TryStreamExt};
 
 use crate::{
+    rate_limit::ShopifyRestCallLimit,
+    retry::RetryPolicy,
     utils::{self, ReadJsonTreeSteps},
     Shopify, ShopifyAPIError,
 };
 
+/// Wait after a 429 without a usable `Retry-After` header
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+/// Longest wait accepted from a `Retry-After` header
+const MAX_RETRY_AFTER: Duration = crate::rate_limit::MAX_WAIT;
+
 pub enum ShopifyAPIRestType<'a> {
     Get(&'a str, &'a HashMap<&'a str, &'a str>),
     Post(
@@ -20,52 +30,158 @@ pub enum ShopifyAPIRestType<'a> {
     Delete(&'a str, &'a HashMap<&'a str, &'a str>),
 }
 
+/// A page of a REST response, with the `Link` header pagination
+#[derive(Debug, Clone)]
+pub struct ShopifyRestPage<T> {
+    pub data: T,
+    /// URL of the next page (`rel="next"`)
+    pub next: Option<String>,
+    /// URL of the previous page (`rel="previous"`)
+    pub previous: Option<String>,
+}
+
+impl<T> ShopifyRestPage<T> {
+    /// Get the `page_info` parameter of the next page
+    pub fn next_page_info(&self) -> Option<String> {
+        self.next.as_deref().and_then(page_info)
+    }
+
+    /// Get the `page_info` parameter of the previous page
+    pub fn previous_page_info(&self) -> Option<String> {
+        self.previous.as_deref().and_then(page_info)
+    }
+}
+
+enum RestPaginationState {
+    First,
+    Next(String),
+    Done,
+}
+
+fn page_info(url: &str) -> Option<String> {
+    reqwest::Url::parse(url)
+        .ok()?
+        .query_pairs()
+        .find(|(key, _)| key == "page_info")
+        .map(|(_, value)| value.to_string())
+}
+
+/// Parse a `Link` header into its `rel` and URL pairs
+/// # Example
+/// ```
+/// use shopify_api::rest::parse_link_header;
+///
+/// let links = parse_link_header(r#"<https://myshop.myshopify.com/admin/api/2024-04/products.json?limit=50&page_info=abc>; rel="previous", <https://myshop.myshopify.com/admin/api/2024-04/products.json?limit=50&page_info=def>; rel="next""#);
+///
+/// assert_eq!(links.get("next").unwrap(), "https://myshop.myshopify.com/admin/api/2024-04/products.json?limit=50&page_info=def");
+/// assert_eq!(links.get("previous").unwrap(), "https://myshop.myshopify.com/admin/api/2024-04/products.json?limit=50&page_info=abc");
+/// ```
+pub fn parse_link_header(header: &str) -> HashMap<String, String> {
+    header
+        .split(',')
+        .filter_map(|link| {
+            let mut parts = link.split(';');
+            let url = parts.next()?.trim().strip_prefix('<')?.strip_suffix('>')?;
+
+            parts
+                .filter_map(|param| param.trim().strip_prefix("rel="))
+                .map(|rel| (rel.trim_matches('"').to_string(), url.to_string()))
+                .next()
+        })
+        .collect()
+}
+
 async fn shopify_rest_query<ReturnType>(
     (shopify, endpoint, json_finder): &(
         &Shopify,
         &ShopifyAPIRestType<'_>,
         &Option<Vec<ReadJsonTreeSteps<'_>>>,
     ),
-) -> Result<ReturnType, ShopifyAPIError>
+) -> Result<ShopifyRestPage<ReturnType>, ShopifyAPIError>
 where
     ReturnType: serde::de::DeserializeOwned,
 {
     // Prepare the client
-    let client = reqwest::Client::new();
+    let client = shopify.client();
     let mut headers = reqwest::header::HeaderMap::new();
     headers.insert("Content-Type", "application/json".parse().unwrap());
     headers.insert("X-Shopify-Access-Token", shopify.api_key.parse().unwrap());
 
     let req = match endpoint {
         ShopifyAPIRestType::Get(url, params) => client
-            .get(shopify.get_api_endpoint(url))
+            .get(shopify.rest_endpoint_url(url)?)
             .headers(headers)
             .query(params),
 
         ShopifyAPIRestType::Post(url, params, body) => client
-            .post(shopify.get_api_endpoint(url))
+            .post(shopify.rest_endpoint_url(url)?)
             .headers(headers)
             .query(params)
             .body(body.to_string()),
 
         ShopifyAPIRestType::Put(url, params, body) => client
-            .put(shopify.get_api_endpoint(url))
+            .put(shopify.rest_endpoint_url(url)?)
             .headers(headers)
             .query(params)
             .body(body.to_string()),
 
         ShopifyAPIRestType::Delete(url, params) => client
-            .delete(shopify.get_api_endpoint(url))
+            .delete(shopify.rest_endpoint_url(url)?)
             .headers(headers)
             .query(params),
     };
 
     log::debug!("Request: {:?}", req);
 
+    // Wait for the call limiter
+    shopify.rest_limiter.acquire().await;
+
     // Connection Response
     let res = req.send().await?;
 
+    // Feed the call limiter
+    if let Some(limit) = res
+        .headers()
+        .get("X-Shopify-Shop-Api-Call-Limit")
+        .and_then(|limit| limit.to_str().ok())
+        .and_then(|limit| limit.parse::<ShopifyRestCallLimit>().ok())
+    {
+        shopify.rest_limiter.update(&limit);
+    }
+
+    if res.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        let retry_after = res
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|retry_after| retry_after.to_str().ok())
+            .and_then(|retry_after| retry_after.trim().parse::<f64>().ok())
+            .and_then(|retry_after| Duration::try_from_secs_f64(retry_after.max(0.0)).ok())
+            .unwrap_or(DEFAULT_RETRY_AFTER)
+            .min(MAX_RETRY_AFTER);
+
+        log::debug!("REST request throttled, retrying after {:?}", retry_after);
+        shopify.rest_limiter.retry_after(retry_after);
+
+        let request_id = crate::request_id(res.headers());
+        let body = res.text().await.unwrap_or_default();
+        return Err(ShopifyAPIError::from_status(
+            reqwest::StatusCode::TOO_MANY_REQUESTS,
+            body,
+            request_id,
+        ));
+    }
+
+    // Link header pagination
+    let links = res
+        .headers()
+        .get(reqwest::header::LINK)
+        .and_then(|link| link.to_str().ok())
+        .map(parse_link_header)
+        .unwrap_or_default();
+
     // Connection data
+    let status = res.status();
+    let request_id = crate::request_id(res.headers());
     let body = res.text().await;
     if body.is_err() {
         return Err(ShopifyAPIError::ResponseBroken);
@@ -73,6 +189,10 @@ where
 
     let body = body.unwrap();
 
+    if !status.is_success() {
+        return Err(ShopifyAPIError::from_status(status, body, request_id));
+    }
+
     let json: serde_json::Value =
         serde_json::from_str(&body).map_err(ShopifyAPIError::JsonParseError)?;
 
@@ -88,21 +208,11 @@ where
         None => &json,
     };
 
-    let json = match serde_json::to_string(json) {
-        Ok(v) => v,
-        Err(_) => {
-            return Err(ShopifyAPIError::NotWantedJsonFormat(json.to_string()));
-        }
-    };
-
-    let json = match serde_json::from_str(&json) {
-        Ok(v) => v,
-        Err(_) => {
-            return Err(ShopifyAPIError::NotWantedJsonFormat(json.to_string()));
-        }
-    };
-
-    Ok(json)
+    Ok(ShopifyRestPage {
+        data: utils::from_json_value(json)?,
+        next: links.get("next").cloned(),
+        previous: links.get("previous").cloned(),
+    })
 }
 
 impl Shopify {
@@ -156,12 +266,155 @@ impl Shopify {
         rest_query: &ShopifyAPIRestType<'_>,
         json_finder: &Option<Vec<ReadJsonTreeSteps<'_>>>,
     ) -> Result<ReturnType, ShopifyAPIError>
+    where
+        ReturnType: serde::de::DeserializeOwned,
+    {
+        self.rest_query_with_retry(rest_query, json_finder, self.retry_policy())
+            .await
+    }
+
+    /// Query REST shopify api with a specific retry policy
+    ///
+    /// `POST` requests are considered non-idempotent, see [`RetryPolicy`].
+    pub async fn rest_query_with_retry<ReturnType>(
+        &self,
+        rest_query: &ShopifyAPIRestType<'_>,
+        json_finder: &Option<Vec<ReadJsonTreeSteps<'_>>>,
+        retry_policy: &RetryPolicy,
+    ) -> Result<ReturnType, ShopifyAPIError>
+    where
+        ReturnType: serde::de::DeserializeOwned,
+    {
+        let page = self
+            .rest_query_page_with_retry(rest_query, json_finder, retry_policy)
+            .await?;
+
+        Ok(page.data)
+    }
+
+    /// Query REST shopify api, keeping the `Link` header pagination of the response
+    ///
+    /// The endpoint can also be the absolute URL of a page, as found in `next` and `previous`.
+    /// # Example
+    /// ```no_run
+    /// use std::collections::HashMap;
+    /// use shopify_api::*;
+    /// use shopify_api::utils::ReadJsonTreeSteps;
+    /// use shopify_api::rest::{ShopifyAPIRestType, ShopifyRestPage};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let shopify = Shopify::new("myshop", "myapikey", String::from("2024-04"), None);
+    ///     let json_finder = Some(vec![ReadJsonTreeSteps::Key("products")]);
+    ///
+    ///     let params = HashMap::from([("limit", "50")]);
+    ///     let page: ShopifyRestPage<Vec<serde_json::Value>> = shopify
+    ///         .rest_query_page(&ShopifyAPIRestType::Get("products.json", &params), &json_finder)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     if let Some(next) = &page.next {
+    ///         let next_page: ShopifyRestPage<Vec<serde_json::Value>> = shopify
+    ///             .rest_query_page(&ShopifyAPIRestType::Get(next, &HashMap::new()), &json_finder)
+    ///             .await
+    ///             .unwrap();
+    ///     }
+    /// }
+    /// ```
+    pub async fn rest_query_page<ReturnType>(
+        &self,
+        rest_query: &ShopifyAPIRestType<'_>,
+        json_finder: &Option<Vec<ReadJsonTreeSteps<'_>>>,
+    ) -> Result<ShopifyRestPage<ReturnType>, ShopifyAPIError>
+    where
+        ReturnType: serde::de::DeserializeOwned,
+    {
+        self.rest_query_page_with_retry(rest_query, json_finder, self.retry_policy())
+            .await
+    }
+
+    /// Query REST shopify api with a specific retry policy, keeping the `Link` header pagination
+    pub async fn rest_query_page_with_retry<ReturnType>(
+        &self,
+        rest_query: &ShopifyAPIRestType<'_>,
+        json_finder: &Option<Vec<ReadJsonTreeSteps<'_>>>,
+        retry_policy: &RetryPolicy,
+    ) -> Result<ShopifyRestPage<ReturnType>, ShopifyAPIError>
     where
         ReturnType: serde::de::DeserializeOwned,
     {
         let args = (self, rest_query, json_finder);
-        let response_json = utils::retry_async(10, shopify_rest_query::<ReturnType>, &args).await?;
+        let idempotent = !matches!(rest_query, ShopifyAPIRestType::Post(..));
+
+        retry_policy
+            .run(idempotent, || shopify_rest_query::<ReturnType>(&args))
+            .await
+    }
+
+    /// Iterate over every item of a REST list endpoint, following the `rel="next"` links
+    ///
+    /// `json_finder` points to the list of items in the response, e.g. `products` for `products.json`.
+    /// # Example
+    /// ```no_run
+    /// use std::collections::HashMap;
+    /// use shopify_api::*;
+    /// use shopify_api::utils::ReadJsonTreeSteps;
+    /// use futures::TryStreamExt;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let shopify = Shopify::new("myshop", "myapikey", String::from("2024-04"), None);
+    ///     let params = HashMap::from([("limit", "250")]);
+    ///
+    ///     let products: Vec<serde_json::Value> = shopify
+    ///         .rest_paginate("products.json", &params, vec![ReadJsonTreeSteps::Key("products")])
+    ///         .try_collect()
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub fn rest_paginate<'a, ItemType>(
+        &'a self,
+        endpoint: &'a str,
+        params: &'a HashMap<&'a str, &'a str>,
+        json_finder: Vec<ReadJsonTreeSteps<'a>>,
+    ) -> impl Stream<Item = Result<ItemType, ShopifyAPIError>> + 'a
+    where
+        ItemType: serde::de::DeserializeOwned + 'a,
+    {
+        let json_finder = Some(json_finder);
+
+        stream::try_unfold(RestPaginationState::First, move |state| {
+            let json_finder = json_finder.clone();
+
+            async move {
+                let page: ShopifyRestPage<Vec<ItemType>> = match state {
+                    RestPaginationState::Done => return Ok(None),
+                    RestPaginationState::First => {
+                        self.rest_query_page(
+                            &ShopifyAPIRestType::Get(endpoint, params),
+                            &json_finder,
+                        )
+                        .await?
+                    }
+                    RestPaginationState::Next(url) => {
+                        self.rest_query_page(
+                            &ShopifyAPIRestType::Get(&url, &HashMap::new()),
+                            &json_finder,
+                        )
+                        .await?
+                    }
+                };
 
-        Ok(response_json)
+                let state = match page.next {
+                    Some(url) => RestPaginationState::Next(url),
+                    None => RestPaginationState::Done,
+                };
+
+                Ok::<_, ShopifyAPIError>(Some((page.data, state)))
+            }
+        })
+        .map_ok(|items| stream::iter(items.into_iter().map(Ok)))
+        .try_flatten()
     }
 }
@@ -0,0 +1,212 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::ShopifyAPIError;
+
+type RetryPredicate = Arc<dyn Fn(&ShopifyAPIError) -> bool + Send + Sync>;
+
+/// Retry policy of the REST and GraphQL requests
+///
+/// Delays grow exponentially from `base_delay` up to `max_delay`. Non-idempotent requests
+/// (REST `POST`, GraphQL mutations) are only retried when Shopify did not process them
+/// (throttled, connection refused) unless `retry_non_idempotent` is set.
+/// # Example
+/// ```
+/// use shopify_api::retry::RetryPolicy;
+/// use shopify_api::ShopifyAPIError;
+/// use std::time::Duration;
+///
+/// let policy = RetryPolicy::default()
+///     .with_max_attempts(3)
+///     .with_base_delay(Duration::from_millis(100))
+///     .with_jitter(false);
+///
+/// assert_eq!(policy.delay(1), Duration::from_millis(100));
+/// assert_eq!(policy.delay(3), Duration::from_millis(400));
+///
+/// let throttled = ShopifyAPIError::Throttled { body: String::new(), request_id: None };
+/// assert!(policy.is_retryable(&throttled, false));
+/// assert!(!policy.is_retryable(&ShopifyAPIError::NotWantedJsonFormat("{}".to_string()), true));
+/// ```
+#[derive(Clone)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub base_delay: Duration,
+    /// Upper bound of the delay between two attempts
+    pub max_delay: Duration,
+    /// Randomize the delays so concurrent clients do not retry at the same time
+    pub jitter: bool,
+    /// HTTP statuses worth retrying
+    pub retryable_statuses: Vec<u16>,
+    /// Retry non-idempotent requests even if Shopify may have processed them
+    pub retry_non_idempotent: bool,
+    retry_if: Option<RetryPredicate>,
+}
+
+impl std::fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("base_delay", &self.base_delay)
+            .field("max_delay", &self.max_delay)
+            .field("jitter", &self.jitter)
+            .field("retryable_statuses", &self.retryable_statuses)
+            .field("retry_non_idempotent", &self.retry_non_idempotent)
+            .field("retry_if", &self.retry_if.is_some())
+            .finish()
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 10,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+            retryable_statuses: vec![429, 500, 502, 503, 504],
+            retry_non_idempotent: false,
+            retry_if: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy which never retries
+    pub fn none() -> RetryPolicy {
+        RetryPolicy::default().with_max_attempts(1)
+    }
+
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> RetryPolicy {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    pub fn with_base_delay(mut self, base_delay: Duration) -> RetryPolicy {
+        self.base_delay = base_delay;
+        self
+    }
+
+    pub fn with_max_delay(mut self, max_delay: Duration) -> RetryPolicy {
+        self.max_delay = max_delay;
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: bool) -> RetryPolicy {
+        self.jitter = jitter;
+        self
+    }
+
+    pub fn with_retryable_statuses(mut self, statuses: Vec<u16>) -> RetryPolicy {
+        self.retryable_statuses = statuses;
+        self
+    }
+
+    pub fn with_retry_non_idempotent(mut self, retry_non_idempotent: bool) -> RetryPolicy {
+        self.retry_non_idempotent = retry_non_idempotent;
+        self
+    }
+
+    /// Replace the default classification of the retryable errors
+    /// # Example
+    /// ```
+    /// use shopify_api::retry::RetryPolicy;
+    /// use shopify_api::ShopifyAPIError;
+    ///
+    /// let policy = RetryPolicy::default()
+    ///     .with_retry_if(|error| matches!(error, ShopifyAPIError::ResponseBroken));
+    ///
+    /// assert!(policy.is_retryable(&ShopifyAPIError::ResponseBroken, true));
+    /// let throttled = ShopifyAPIError::Throttled { body: String::new(), request_id: None };
+    /// assert!(!policy.is_retryable(&throttled, true));
+    /// ```
+    pub fn with_retry_if<F>(mut self, retry_if: F) -> RetryPolicy
+    where
+        F: Fn(&ShopifyAPIError) -> bool + Send + Sync + 'static,
+    {
+        self.retry_if = Some(Arc::new(retry_if));
+        self
+    }
+
+    /// Whether a request which failed with this error should be retried
+    pub fn is_retryable(&self, error: &ShopifyAPIError, idempotent: bool) -> bool {
+        if !idempotent && !self.retry_non_idempotent && !Self::is_unprocessed(error) {
+            return false;
+        }
+
+        if let Some(retry_if) = &self.retry_if {
+            return retry_if(error);
+        }
+
+        match error {
+            ShopifyAPIError::Throttled { .. } | ShopifyAPIError::ResponseBroken => true,
+            ShopifyAPIError::ConnectionFailed(e) => e.is_timeout() || e.is_connect(),
+            _ => error
+                .status()
+                .map(|status| self.retryable_statuses.contains(&status.as_u16()))
+                .unwrap_or(false),
+        }
+    }
+
+    /// Delay to wait before the given retry (starting at 1)
+    pub fn delay(&self, retry: u32) -> Duration {
+        let exponent = retry.saturating_sub(1).min(31);
+        let delay = self
+            .base_delay
+            .saturating_mul(1 << exponent)
+            .min(self.max_delay);
+
+        if self.jitter {
+            delay / 2 + delay.mul_f64(fastrand::f64() / 2.0)
+        } else {
+            delay
+        }
+    }
+
+    /// Run `func` until it succeeds, returns a non retryable error or the attempts are exhausted
+    pub async fn run<F, Fut, Out>(
+        &self,
+        idempotent: bool,
+        mut func: F,
+    ) -> Result<Out, ShopifyAPIError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<Out, ShopifyAPIError>>,
+    {
+        let mut attempt: u32 = 1;
+
+        loop {
+            match func().await {
+                Ok(out) => return Ok(out),
+                Err(error) => {
+                    if attempt >= self.max_attempts || !self.is_retryable(&error, idempotent) {
+                        return Err(error);
+                    }
+
+                    let delay = self.delay(attempt);
+                    log::debug!(
+                        "Attempt {} failed with {:?}, retrying in {:?}",
+                        attempt,
+                        error,
+                        delay
+                    );
+                    tokio::time::sleep(delay).await;
+
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    /// Errors which guarantee the request was not processed by Shopify
+    fn is_unprocessed(error: &ShopifyAPIError) -> bool {
+        match error {
+            ShopifyAPIError::Throttled { .. } => true,
+            ShopifyAPIError::ConnectionFailed(e) => e.is_connect(),
+            _ => false,
+        }
+    }
+}
@@ -179,10 +179,7 @@ pub fn deserialize_from_value<T>(value: serde_json::Value) -> Result<T, String>
 where
     T: serde::de::DeserializeOwned,
 {
-    let json_string = serde_json::to_string(&value).unwrap();
-    let jd = &mut serde_json::Deserializer::from_str(&json_string);
-
-    let result: Result<T, _> = serde_path_to_error::deserialize(jd);
+    let result: Result<T, _> = serde_path_to_error::deserialize(value);
     match result {
         Ok(customer) => Ok(customer),
         Err(err) => Err(format!(
@@ -205,12 +202,51 @@ where
     }
 }
 
+/// Deserialize a JSON value into a `ShopifyAPIError::Deserialization` aware result
+///
+/// With the `debug` feature, the error carries the path of the field which failed.
+/// # Example
+/// ```
+/// use shopify_api::utils::from_json_value;
+/// use shopify_api::ShopifyAPIError;
+/// use serde_json::json;
+///
+/// let value = json!({ "products": [{ "id": "not a number" }] });
+/// let result = from_json_value::<std::collections::HashMap<String, Vec<std::collections::HashMap<String, u64>>>>(&value);
+///
+/// assert!(matches!(result, Err(ShopifyAPIError::Deserialization { .. })));
+/// ```
+#[cfg(feature = "debug")]
+pub fn from_json_value<T>(value: &serde_json::Value) -> Result<T, crate::ShopifyAPIError>
+where
+    T: serde::de::DeserializeOwned,
+{
+    serde_path_to_error::deserialize(value).map_err(|err| crate::ShopifyAPIError::Deserialization {
+        path: Some(err.path().to_string()),
+        message: err.inner().to_string(),
+        body: value.to_string(),
+    })
+}
+
+#[cfg(not(feature = "debug"))]
+pub fn from_json_value<T>(value: &serde_json::Value) -> Result<T, crate::ShopifyAPIError>
+where
+    T: serde::de::DeserializeOwned,
+{
+    T::deserialize(value).map_err(|err| crate::ShopifyAPIError::Deserialization {
+        path: None,
+        message: err.to_string(),
+        body: value.to_string(),
+    })
+}
+
 #[cfg(feature = "debug")]
 pub fn deserialize_from_str<T>(json_string: &str) -> Result<T, String>
 where
     T: serde::de::DeserializeOwned,
 {
-    let value: serde_json::Value = serde_json::from_str(json_string).unwrap();
+    let value: serde_json::Value =
+        serde_json::from_str(json_string).map_err(|err| format!("Error parsing JSON: {}", err))?;
     deserialize_from_value(value)
 }
 
@@ -219,13 +255,7 @@ pub fn deserialize_from_str<T>(json_string: &str) -> Result<T, String>
 where
     T: serde::de::DeserializeOwned,
 {
-    use serde_json::Error;
-
-    let value: serde_json::Value = serde_json::from_str(json_string).unwrap();
-    let result: Result<T, Error> = serde_json::from_value(value);
-
-    match result {
-        Ok(customer) => Ok(customer),
-        Err(err) => Err(format!("Error parsing JSON: {}", err)),
-    }
+    let value: serde_json::Value =
+        serde_json::from_str(json_string).map_err(|err| format!("Error parsing JSON: {}", err))?;
+    deserialize_from_value(value)
 }
@@ -0,0 +1,235 @@
+use crate::{Shopify, ShopifyAPIError};
+use futures::future::BoxFuture;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// State of a delivery in a [`WebhookDedupStore`] when a handler claims it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookClaim {
+    /// The delivery was not recorded, the handler processes it
+    Claimed,
+    /// Another attempt of the delivery is being processed
+    InProgress,
+    /// The delivery was already processed
+    Processed,
+}
+
+/// Store of the webhook deliveries being processed or already processed
+///
+/// Shopify delivers webhooks at least once. The webhook handlers claim the
+/// [`WebhookEvent::dedup_key`](super::handler::WebhookEvent::dedup_key) of every delivery before
+/// invoking their callback, then complete the claim when the callback succeeds or release it
+/// when it fails. Deliveries already processed are skipped, and deliveries still in progress are
+/// answered with a retryable status so that Shopify attempts them again.
+/// # Example
+/// ```
+/// use futures::future::BoxFuture;
+/// use shopify_api::webhooks::dedup::{WebhookClaim, WebhookDedupStore};
+/// use shopify_api::ShopifyAPIError;
+///
+/// /// A store which never deduplicates
+/// struct NoDedup;
+///
+/// impl WebhookDedupStore for NoDedup {
+///     fn claim<'a>(&'a self, _key: &'a str) -> BoxFuture<'a, Result<WebhookClaim, ShopifyAPIError>> {
+///         Box::pin(async { Ok(WebhookClaim::Claimed) })
+///     }
+///
+///     fn complete<'a>(&'a self, _key: &'a str) -> BoxFuture<'a, Result<(), ShopifyAPIError>> {
+///         Box::pin(async { Ok(()) })
+///     }
+///
+///     fn release<'a>(&'a self, _key: &'a str) -> BoxFuture<'a, Result<(), ShopifyAPIError>> {
+///         Box::pin(async { Ok(()) })
+///     }
+/// }
+/// ```
+pub trait WebhookDedupStore: Send + Sync {
+    /// Claim a delivery before processing it, returns its previous state if it was recorded
+    fn claim<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<WebhookClaim, ShopifyAPIError>>;
+
+    /// Record a claimed delivery as processed
+    fn complete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<(), ShopifyAPIError>>;
+
+    /// Forget a claimed delivery, so that its next attempt is processed again
+    fn release<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<(), ShopifyAPIError>>;
+}
+
+/// In memory [`WebhookDedupStore`], forgetting processed deliveries after `ttl`
+///
+/// The default `ttl` is 48 hours, the period during which Shopify retries a delivery. A claim
+/// which is neither completed nor released, e.g. because its callback panicked, expires after
+/// `claim_ttl`, 5 minutes by default.
+///
+/// Expired deliveries are pruned at most once per [`MemoryDedupStore::PRUNE_INTERVAL`], so a
+/// claim does not scan the whole store.
+/// # Example
+/// ```
+/// use shopify_api::webhooks::dedup::{MemoryDedupStore, WebhookClaim, WebhookDedupStore};
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let store = MemoryDedupStore::new(Duration::from_secs(3600));
+///     let key = "b1d4ed5c-2d5b-4a8f-8a2b-5c1f0e5b6f7a";
+///
+///     assert_eq!(store.claim(key).await.unwrap(), WebhookClaim::Claimed);
+///     assert_eq!(store.claim(key).await.unwrap(), WebhookClaim::InProgress);
+///
+///     store.complete(key).await.unwrap();
+///     assert_eq!(store.claim(key).await.unwrap(), WebhookClaim::Processed);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct MemoryDedupStore {
+    ttl: Duration,
+    claim_ttl: Duration,
+    seen: Mutex<SeenDeliveries>,
+}
+
+#[derive(Debug)]
+struct SeenDeliveries {
+    deliveries: HashMap<String, (WebhookClaim, Instant)>,
+    pruned_at: Instant,
+}
+
+impl Default for MemoryDedupStore {
+    fn default() -> Self {
+        MemoryDedupStore::new(Duration::from_secs(48 * 3600))
+    }
+}
+
+impl MemoryDedupStore {
+    /// Minimum delay between two prunings of the expired deliveries
+    pub const PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+    pub fn new(ttl: Duration) -> MemoryDedupStore {
+        MemoryDedupStore {
+            ttl,
+            claim_ttl: Duration::from_secs(5 * 60),
+            seen: Mutex::new(SeenDeliveries {
+                deliveries: HashMap::new(),
+                pruned_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Set how long a delivery stays in progress when its claim is neither completed nor released
+    pub fn with_claim_ttl(mut self, claim_ttl: Duration) -> MemoryDedupStore {
+        self.claim_ttl = claim_ttl;
+        self
+    }
+
+    fn seen(&self) -> std::sync::MutexGuard<'_, SeenDeliveries> {
+        self.seen
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn is_expired(&self, claim: WebhookClaim, recorded_at: Instant, now: Instant) -> bool {
+        let ttl = match claim {
+            WebhookClaim::Processed => self.ttl,
+            _ => self.claim_ttl,
+        };
+
+        now.duration_since(recorded_at) >= ttl
+    }
+}
+
+impl WebhookDedupStore for MemoryDedupStore {
+    fn claim<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<WebhookClaim, ShopifyAPIError>> {
+        Box::pin(async move {
+            let mut seen = self.seen();
+            let now = Instant::now();
+
+            if now.duration_since(seen.pruned_at) >= Self::PRUNE_INTERVAL {
+                seen.deliveries
+                    .retain(|_, (claim, recorded_at)| !self.is_expired(*claim, *recorded_at, now));
+                seen.pruned_at = now;
+            }
+
+            // The delivery may have expired since the last pruning
+            match seen.deliveries.get(key) {
+                Some((claim, recorded_at)) if !self.is_expired(*claim, *recorded_at, now) => {
+                    return Ok(*claim);
+                }
+                _ => {}
+            }
+
+            seen.deliveries
+                .insert(key.to_string(), (WebhookClaim::InProgress, now));
+
+            Ok(WebhookClaim::Claimed)
+        })
+    }
+
+    fn complete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<(), ShopifyAPIError>> {
+        Box::pin(async move {
+            self.seen()
+                .deliveries
+                .insert(key.to_string(), (WebhookClaim::Processed, Instant::now()));
+
+            Ok(())
+        })
+    }
+
+    fn release<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<(), ShopifyAPIError>> {
+        Box::pin(async move {
+            self.seen().deliveries.remove(key);
+
+            Ok(())
+        })
+    }
+}
+
+/// Claim a delivery, a failing store claims every delivery
+pub(crate) async fn claim(store: &dyn WebhookDedupStore, key: &str) -> WebhookClaim {
+    match store.claim(key).await {
+        Ok(claim) => claim,
+        Err(e) => {
+            log::warn!("Failed to claim webhook {}: {}", key, e);
+            WebhookClaim::Claimed
+        }
+    }
+}
+
+pub(crate) async fn complete(store: &dyn WebhookDedupStore, key: &str) {
+    if let Err(e) = store.complete(key).await {
+        log::warn!("Failed to record webhook {}: {}", key, e);
+    }
+}
+
+pub(crate) async fn release(store: &dyn WebhookDedupStore, key: &str) {
+    if let Err(e) = store.release(key).await {
+        log::warn!("Failed to forget webhook {}: {}", key, e);
+    }
+}
+
+impl Shopify {
+    /// Claim a webhook delivery in the dedup store of the client
+    ///
+    /// Returns [`WebhookClaim::Processed`] if the delivery was already processed and
+    /// [`WebhookClaim::InProgress`] if another attempt is being processed. Without a dedup store,
+    /// or if the store fails, every delivery is claimed.
+    pub async fn claim_webhook(&self, key: &str) -> WebhookClaim {
+        match &self.webhook_options.dedup_store {
+            Some(store) => claim(store.as_ref(), key).await,
+            None => WebhookClaim::Claimed,
+        }
+    }
+
+    /// Record a claimed webhook delivery as processed, so that Shopify's next attempts are skipped
+    pub async fn complete_webhook(&self, key: &str) {
+        if let Some(store) = &self.webhook_options.dedup_store {
+            complete(store.as_ref(), key).await;
+        }
+    }
+
+    /// Forget a claimed webhook delivery, so that Shopify's next attempt is processed again
+    pub async fn release_webhook(&self, key: &str) {
+        if let Some(store) = &self.webhook_options.dedup_store {
+            release(store.as_ref(), key).await;
+        }
+    }
+}
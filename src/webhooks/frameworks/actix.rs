@@ -0,0 +1,167 @@
+use crate::webhooks::handler::{WebhookEvent, WebhookRequest};
+use crate::Shopify;
+use actix_web::dev::Payload;
+use actix_web::error::{ErrorInternalServerError, InternalError};
+use actix_web::http::StatusCode;
+use actix_web::web::{self, Bytes};
+use actix_web::{FromRequest, HttpRequest, HttpResponse, Resource};
+use futures::future::LocalBoxFuture;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Largest webhook body accepted by [`Shopify::actix_wrapper`], 2 MiB like axum's default
+///
+/// actix-web's default `web::PayloadConfig` rejects bodies over 256 KiB, which large orders exceed.
+pub const WEBHOOK_PAYLOAD_LIMIT: usize = 2 * 1024 * 1024;
+
+/// Extractor of a verified and parsed Shopify webhook
+///
+/// The `Shopify` client is read from the app data, as the same `Arc<Mutex<Shopify>>` as
+/// [`Shopify::actix_wrapper`], or as a `web::Data<Mutex<Shopify>>`.
+/// Invalid signatures are rejected with `401` and unparsable payloads with `400`.
+///
+/// The body is limited by the `web::PayloadConfig` of the app, 256 KiB unless configured:
+/// register `web::PayloadConfig::new(WEBHOOK_PAYLOAD_LIMIT)` so large payloads are not rejected.
+///
+/// The extractor does not deduplicate deliveries: the dedup store of the client is not used, and
+/// every retry of a delivery reaches the handler. Use [`Shopify::actix_wrapper`], or claim the
+/// [`WebhookEvent::dedup_key`] with [`Shopify::claim_webhook`] then [`Shopify::complete_webhook`]
+/// or [`Shopify::release_webhook`] in the handler.
+/// # Example
+/// ```no_run
+/// use actix_web::{web, App, HttpResponse};
+/// use shopify_api::webhooks::frameworks::actix::{VerifiedWebhook, WEBHOOK_PAYLOAD_LIMIT};
+/// use shopify_api::Shopify;
+/// use std::sync::Arc;
+/// use tokio::sync::Mutex;
+///
+/// async fn webhooks(webhook: VerifiedWebhook) -> HttpResponse {
+///     println!("{} from {:?}", webhook.topic, webhook.shop_domain);
+///     HttpResponse::Ok().finish()
+/// }
+///
+/// let shopify = Shopify::new("myshop", "myapikey", String::from("2024-04"), Some("secret"));
+/// let shopify = Arc::new(Mutex::new(shopify));
+///
+/// let app = App::new()
+///     .app_data(shopify)
+///     .app_data(web::PayloadConfig::new(WEBHOOK_PAYLOAD_LIMIT))
+///     .route("/webhooks", web::post().to(webhooks));
+/// ```
+#[derive(Debug)]
+pub struct VerifiedWebhook(pub WebhookEvent);
+
+impl std::ops::Deref for VerifiedWebhook {
+    type Target = WebhookEvent;
+
+    fn deref(&self) -> &WebhookEvent {
+        &self.0
+    }
+}
+
+impl VerifiedWebhook {
+    pub fn into_inner(self) -> WebhookEvent {
+        self.0
+    }
+}
+
+fn request_headers(req: &HttpRequest) -> Vec<(String, String)> {
+    req.headers()
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect()
+}
+
+impl FromRequest for VerifiedWebhook {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let shopify = req.app_data::<Arc<Mutex<Shopify>>>().cloned().or_else(|| {
+            req.app_data::<web::Data<Mutex<Shopify>>>()
+                .map(|shopify| shopify.clone().into_inner())
+        });
+        let headers = request_headers(req);
+        let body = Bytes::from_request(req, payload);
+
+        Box::pin(async move {
+            let shopify = shopify
+                .ok_or_else(|| ErrorInternalServerError("Shopify is missing from the app data"))?;
+            let request = WebhookRequest::new(headers, body.await?.to_vec());
+
+            let event = shopify
+                .lock()
+                .await
+                .process_webhook(&request)
+                .map_err(|e| {
+                    log::info!("Rejected webhook: {}", e);
+
+                    let status =
+                        StatusCode::from_u16(e.status_code()).unwrap_or(StatusCode::BAD_REQUEST);
+                    InternalError::new(e, status)
+                })?;
+
+            Ok(VerifiedWebhook(event))
+        })
+    }
+}
+
+impl Shopify {
+    /// Build an actix-web `Resource` receiving the webhooks on `path`
+    ///
+    /// Requests are handled by [`Shopify::dispatch_webhook`] and answered with the status of its
+    /// [`WebhookOutcome`](crate::webhooks::handler::WebhookOutcome). Bodies are accepted up to
+    /// [`WEBHOOK_PAYLOAD_LIMIT`].
+    /// # Example
+    /// ```no_run
+    /// use actix_web::App;
+    /// use shopify_api::Shopify;
+    /// use std::sync::Arc;
+    /// use tokio::sync::Mutex;
+    ///
+    /// let shopify = Shopify::new("myshop", "myapikey", String::from("2024-04"), Some("secret"));
+    ///
+    /// let app = App::new().service(Shopify::actix_wrapper(
+    ///     "/webhooks",
+    ///     Arc::new(Mutex::new(shopify)),
+    ///     (),
+    ///     |event, _shopify, _extra| async move {
+    ///         println!("{} from {:?}: {:?}", event.topic, event.shop_domain, event.payload);
+    ///         Ok(())
+    ///     },
+    /// ));
+    /// ```
+    pub fn actix_wrapper<F, Fut, T>(
+        path: &str,
+        shopify: Arc<Mutex<Shopify>>,
+        extra_data: T,
+        callback: F,
+    ) -> Resource
+    where
+        T: Clone + 'static,
+        F: Fn(WebhookEvent, Arc<Mutex<Shopify>>, T) -> Fut + Clone + 'static,
+        Fut: Future<Output = Result<(), String>> + 'static,
+    {
+        let handler = move |req: HttpRequest, body: Bytes| {
+            let shopify = shopify.clone();
+            let callback = callback.clone();
+            let extra_data = extra_data.clone();
+
+            async move {
+                let request = WebhookRequest::new(request_headers(&req), body.to_vec());
+                let outcome =
+                    Shopify::dispatch_webhook(&shopify, &request, extra_data, callback).await;
+
+                HttpResponse::build(
+                    StatusCode::from_u16(outcome.status_code()).unwrap_or(StatusCode::BAD_REQUEST),
+                )
+                .body(outcome.message())
+            }
+        };
+
+        web::resource(path)
+            .app_data(web::PayloadConfig::new(WEBHOOK_PAYLOAD_LIMIT))
+            .route(web::post().to(handler))
+    }
+}
@@ -0,0 +1,71 @@
+use crate::webhooks::handler::{WebhookEvent, WebhookRequest};
+use crate::Shopify;
+use axum::body::Bytes;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::Router;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+impl Shopify {
+    /// Build an axum `Router` receiving the webhooks on `path`
+    ///
+    /// Requests are handled by [`Shopify::dispatch_webhook`] and answered with the status of its
+    /// [`WebhookOutcome`](crate::webhooks::handler::WebhookOutcome).
+    /// # Example
+    /// ```no_run
+    /// use shopify_api::Shopify;
+    /// use std::sync::Arc;
+    /// use tokio::sync::Mutex;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let shopify = Shopify::new("myshop", "myapikey", String::from("2024-04"), Some("secret"));
+    ///
+    ///     let app = Shopify::axum_wrapper(
+    ///         "/webhooks",
+    ///         Arc::new(Mutex::new(shopify)),
+    ///         (),
+    ///         |event, _shopify, _extra| async move {
+    ///             println!("{} from {:?}: {:?}", event.topic, event.shop_domain, event.payload);
+    ///             Ok(())
+    ///         },
+    ///     );
+    ///
+    ///     let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await.unwrap();
+    ///     axum::serve(listener, app).await.unwrap();
+    /// }
+    /// ```
+    pub fn axum_wrapper<F, Fut, T>(
+        path: &str,
+        shopify: Arc<Mutex<Shopify>>,
+        extra_data: T,
+        callback: F,
+    ) -> Router
+    where
+        T: Clone + Send + Sync + 'static,
+        F: Fn(WebhookEvent, Arc<Mutex<Shopify>>, T) -> Fut + Clone + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), String>> + Send,
+    {
+        let path = format!("/{}", path.trim_start_matches('/'));
+
+        let handler = move |headers: HeaderMap, body: Bytes| async move {
+            let request = WebhookRequest::new(
+                headers
+                    .iter()
+                    .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?))),
+                body.to_vec(),
+            );
+
+            let outcome = Shopify::dispatch_webhook(&shopify, &request, extra_data, callback).await;
+
+            (
+                StatusCode::from_u16(outcome.status_code()).unwrap_or(StatusCode::BAD_REQUEST),
+                outcome.message(),
+            )
+        };
+
+        Router::new().route(&path, post(handler))
+    }
+}
@@ -1,49 +1,227 @@
-#![cfg(feature = "warp-wrapper")]
+#[cfg(feature = "actix-wrapper")]
+pub mod actix;
+#[cfg(feature = "axum-wrapper")]
+pub mod axum;
+#[cfg(feature = "warp-wrapper")]
 pub mod warp;
 
 // https://shopify.dev/docs/api/admin-rest/2024-04/resources/webhook#event-topics
 
+use super::topic::WebhookTopic;
+use crate::graphql::types::{DateTime, Decimal};
+use crate::{utils, Gid, ShopifyAPIError};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+/// A webhook payload, typed according to its topic
+///
+/// Topics without a typed payload are kept as JSON in `Other`.
 #[derive(Debug)]
 pub enum ShopifyWebhook {
+    AppUninstalled(Shop),
+    CartsCreate(Cart),
+    CartsUpdate(Cart),
+    CheckoutsCreate(Checkout),
+    CheckoutsUpdate(Checkout),
+    CheckoutsDelete(DeletedResource),
+    CollectionsCreate(Collection),
+    CollectionsUpdate(Collection),
+    CollectionsDelete(DeletedResource),
+    CustomersCreate(Customer),
+    CustomersUpdate(Customer),
+    CustomersEnable(Customer),
+    CustomersDisable(Customer),
+    CustomersDelete(DeletedResource),
+    FulfillmentsCreate(OrderFulfillment),
+    FulfillmentsUpdate(OrderFulfillment),
     InventoryItemCreate(InventoryItem),
     InventoryItemUpdate(InventoryItem),
     InventoryItemDelete(InventoryItem),
     InventoryLevelConnect(InventoryLevel),
     InventoryLevelDisconnect(InventoryLevel),
     InventoryLevelUpdate(InventoryLevel),
-    CustomersCreate(Customer),
-    CustomersUpdate(Customer),
     OrdersCreate(Order),
     OrdersUpdated(Order),
+    OrdersCancelled(Order),
+    OrdersFulfilled(Order),
+    OrdersPaid(Order),
+    OrdersPartiallyFulfilled(Order),
+    OrdersDelete(DeletedResource),
+    ProductsCreate(Product),
+    ProductsUpdate(Product),
+    ProductsDelete(DeletedResource),
+    RefundsCreate(OrderRefund),
+    ShopUpdate(Shop),
+    ThemesCreate(Theme),
+    ThemesUpdate(Theme),
+    ThemesPublish(Theme),
+    ThemesDelete(DeletedResource),
     Other((String, Value)),
 }
 
+impl ShopifyWebhook {
+    /// Parse the body of a webhook according to its topic
+    /// # Example
+    /// ```
+    /// use shopify_api::webhooks::frameworks::ShopifyWebhook;
+    /// use shopify_api::webhooks::topic::WebhookTopic;
+    ///
+    /// let webhook = ShopifyWebhook::parse(&WebhookTopic::ProductsDelete, br#"{"id": 788032119674292922}"#).unwrap();
+    /// assert!(matches!(webhook, ShopifyWebhook::ProductsDelete(product) if product.id == 788032119674292922));
+    ///
+    /// let webhook = ShopifyWebhook::parse(&WebhookTopic::LocalesCreate, br#"{"locale": "fr"}"#).unwrap();
+    /// assert!(matches!(webhook, ShopifyWebhook::Other((topic, _)) if topic == "locales/create"));
+    /// ```
+    /// # Errors
+    /// This function returns an error if the body is not a valid payload of the topic
+    pub fn parse(topic: &WebhookTopic, body: &[u8]) -> Result<ShopifyWebhook, ShopifyAPIError> {
+        let json: Value = serde_json::from_slice(body)?;
+
+        Ok(match topic {
+            WebhookTopic::AppUninstalled => {
+                ShopifyWebhook::AppUninstalled(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::CartsCreate => {
+                ShopifyWebhook::CartsCreate(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::CartsUpdate => {
+                ShopifyWebhook::CartsUpdate(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::CheckoutsCreate => {
+                ShopifyWebhook::CheckoutsCreate(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::CheckoutsUpdate => {
+                ShopifyWebhook::CheckoutsUpdate(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::CheckoutsDelete => {
+                ShopifyWebhook::CheckoutsDelete(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::CollectionsCreate => {
+                ShopifyWebhook::CollectionsCreate(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::CollectionsUpdate => {
+                ShopifyWebhook::CollectionsUpdate(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::CollectionsDelete => {
+                ShopifyWebhook::CollectionsDelete(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::CustomersCreate => {
+                ShopifyWebhook::CustomersCreate(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::CustomersUpdate => {
+                ShopifyWebhook::CustomersUpdate(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::CustomersEnable => {
+                ShopifyWebhook::CustomersEnable(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::CustomersDisable => {
+                ShopifyWebhook::CustomersDisable(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::CustomersDelete => {
+                ShopifyWebhook::CustomersDelete(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::FulfillmentsCreate => {
+                ShopifyWebhook::FulfillmentsCreate(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::FulfillmentsUpdate => {
+                ShopifyWebhook::FulfillmentsUpdate(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::InventoryItemsCreate => {
+                ShopifyWebhook::InventoryItemCreate(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::InventoryItemsUpdate => {
+                ShopifyWebhook::InventoryItemUpdate(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::InventoryItemsDelete => {
+                ShopifyWebhook::InventoryItemDelete(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::InventoryLevelsConnect => {
+                ShopifyWebhook::InventoryLevelConnect(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::InventoryLevelsDisconnect => {
+                ShopifyWebhook::InventoryLevelDisconnect(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::InventoryLevelsUpdate => {
+                ShopifyWebhook::InventoryLevelUpdate(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::OrdersCreate => {
+                ShopifyWebhook::OrdersCreate(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::OrdersUpdated => {
+                ShopifyWebhook::OrdersUpdated(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::OrdersCancelled => {
+                ShopifyWebhook::OrdersCancelled(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::OrdersFulfilled => {
+                ShopifyWebhook::OrdersFulfilled(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::OrdersPaid => ShopifyWebhook::OrdersPaid(utils::from_json_value(&json)?),
+            WebhookTopic::OrdersPartiallyFulfilled => {
+                ShopifyWebhook::OrdersPartiallyFulfilled(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::OrdersDelete => {
+                ShopifyWebhook::OrdersDelete(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::ProductsCreate => {
+                ShopifyWebhook::ProductsCreate(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::ProductsUpdate => {
+                ShopifyWebhook::ProductsUpdate(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::ProductsDelete => {
+                ShopifyWebhook::ProductsDelete(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::RefundsCreate => {
+                ShopifyWebhook::RefundsCreate(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::ShopUpdate => ShopifyWebhook::ShopUpdate(utils::from_json_value(&json)?),
+            WebhookTopic::ThemesCreate => {
+                ShopifyWebhook::ThemesCreate(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::ThemesUpdate => {
+                ShopifyWebhook::ThemesUpdate(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::ThemesPublish => {
+                ShopifyWebhook::ThemesPublish(utils::from_json_value(&json)?)
+            }
+            WebhookTopic::ThemesDelete => {
+                ShopifyWebhook::ThemesDelete(utils::from_json_value(&json)?)
+            }
+            _ => ShopifyWebhook::Other((topic.to_string(), json)),
+        })
+    }
+}
+
+/// The payload of the `*/delete` topics, only holding the id of the deleted resource
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DeletedResource {
+    pub id: u64,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct InventoryLevel {
     pub inventory_item_id: u64,
     pub location_id: u64,
     pub available: Option<i64>,
-    pub updated_at: Option<String>,
-    pub admin_graphql_api_id: Option<String>,
+    pub updated_at: Option<DateTime>,
+    pub admin_graphql_api_id: Option<Gid>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct InventoryItem {
     pub id: u64,
     pub sku: String,
-    pub created_at: String,
-    pub updated_at: String,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
     pub requires_shipping: bool,
-    pub cost: Option<String>,
+    pub cost: Option<Decimal>,
     pub country_code_of_origin: Option<String>,
     pub province_code_of_origin: Option<String>,
     pub harmonized_system_code: Option<u64>,
     pub tracked: bool,
     pub country_harmonized_system_codes: Vec<CountryHarmonizedSystemCode>,
-    pub admin_graphql_api_id: Option<String>,
+    pub admin_graphql_api_id: Option<Gid>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -70,13 +248,13 @@ pub struct Address {
 pub struct Customer {
     pub id: u64,
     pub email: String,
-    pub created_at: String,
-    pub updated_at: String,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
     pub first_name: Option<String>,
     pub last_name: Option<String>,
     pub orders_count: u64,
     pub state: String,
-    pub total_spent: String,
+    pub total_spent: Decimal,
     pub last_order_id: Option<u64>,
     pub note: Option<String>,
     pub verified_email: bool,
@@ -86,7 +264,7 @@ pub struct Customer {
     pub last_order_name: Option<String>,
     pub phone: Option<String>,
     pub addresses: Vec<Address>,
-    pub admin_graphql_api_id: String,
+    pub admin_graphql_api_id: Gid,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -98,31 +276,31 @@ pub struct CountryHarmonizedSystemCode {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Order {
     pub id: u64,
-    pub admin_graphql_api_id: String,
+    pub admin_graphql_api_id: Gid,
     pub app_id: Option<u64>,
     pub browser_ip: Option<String>,
     pub buyer_accepts_marketing: bool,
     pub cancel_reason: Option<String>,
-    pub cancelled_at: Option<String>,
+    pub cancelled_at: Option<DateTime>,
     pub cart_token: Option<String>,
     pub checkout_id: Option<u64>,
     pub checkout_token: Option<String>,
     pub client_details: Option<OrderClientDetails>,
-    pub closed_at: Option<String>,
+    pub closed_at: Option<DateTime>,
     pub confirmation_number: Option<String>,
     pub confirmed: bool,
     pub contact_email: String,
-    pub created_at: String,
+    pub created_at: DateTime,
     pub currency: String,
-    pub current_subtotal_price: String,
+    pub current_subtotal_price: Decimal,
     pub current_subtotal_price_set: OrderPriceSet,
     pub current_total_additional_fees_set: Option<OrderAdditionalFeesSet>,
-    pub current_total_discounts: String,
+    pub current_total_discounts: Decimal,
     pub current_total_discounts_set: OrderPriceSet,
     pub current_total_duties_set: Option<OrderDutiesSet>,
-    pub current_total_price: String,
+    pub current_total_price: Decimal,
     pub current_total_price_set: OrderPriceSet,
-    pub current_total_tax: String,
+    pub current_total_tax: Decimal,
     pub current_total_tax_set: OrderPriceSet,
     pub customer_locale: Option<String>,
     pub device_id: Option<u64>,
@@ -147,13 +325,13 @@ pub struct Order {
     pub phone: Option<String>,
     pub po_number: Option<String>,
     pub presentment_currency: String,
-    pub processed_at: Option<String>,
+    pub processed_at: Option<DateTime>,
     pub reference: Option<String>,
     pub referring_site: Option<String>,
     pub source_identifier: Option<String>,
     pub source_name: String,
     pub source_url: Option<String>,
-    pub subtotal_price: String,
+    pub subtotal_price: Decimal,
     pub subtotal_price_set: OrderPriceSet,
     pub tags: String,
     pub tax_exempt: bool,
@@ -161,19 +339,19 @@ pub struct Order {
     pub taxes_included: bool,
     pub test: bool,
     pub token: String,
-    pub total_discounts: String,
+    pub total_discounts: Decimal,
     pub total_discounts_set: OrderPriceSet,
-    pub total_line_items_price: String,
+    pub total_line_items_price: Decimal,
     pub total_line_items_price_set: OrderPriceSet,
-    pub total_outstanding: String,
-    pub total_price: String,
+    pub total_outstanding: Decimal,
+    pub total_price: Decimal,
     pub total_price_set: OrderPriceSet,
     pub total_shipping_price_set: OrderPriceSet,
-    pub total_tax: String,
+    pub total_tax: Decimal,
     pub total_tax_set: OrderPriceSet,
-    pub total_tip_received: String,
+    pub total_tip_received: Decimal,
     pub total_weight: u64,
-    pub updated_at: String,
+    pub updated_at: DateTime,
     pub user_id: Option<u64>,
     pub billing_address: Option<OrderAddress>,
     pub customer: OrderCustomer,
@@ -187,7 +365,14 @@ pub struct Order {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct OrderClientDetails {}
+pub struct OrderClientDetails {
+    pub accept_language: Option<String>,
+    pub browser_height: Option<u32>,
+    pub browser_ip: Option<String>,
+    pub browser_width: Option<u32>,
+    pub session_hash: Option<String>,
+    pub user_agent: Option<String>,
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OrderPriceSet {
@@ -197,20 +382,26 @@ pub struct OrderPriceSet {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OrderMoney {
-    pub amount: String,
+    pub amount: Decimal,
     pub currency_code: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct OrderAdditionalFeesSet {}
+pub struct OrderAdditionalFeesSet {
+    pub shop_money: OrderMoney,
+    pub presentment_money: OrderMoney,
+}
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct OrderDutiesSet {}
+pub struct OrderDutiesSet {
+    pub shop_money: OrderMoney,
+    pub presentment_money: OrderMoney,
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OrderDiscountCode {
     pub code: Option<String>,
-    pub amount: Option<String>,
+    pub amount: Option<Decimal>,
     #[serde(rename = "type")]
     pub _type: Option<String>,
 }
@@ -224,7 +415,7 @@ pub struct OrderNoteAttribute {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OrderTaxLine {
     pub title: Option<String>,
-    pub price: Option<String>,
+    pub price: Option<Decimal>,
     pub rate: Option<f64>,
 }
 
@@ -251,8 +442,8 @@ pub struct OrderAddress {
 pub struct OrderCustomer {
     pub id: u64,
     pub email: Option<String>,
-    pub created_at: Option<String>,
-    pub updated_at: Option<String>,
+    pub created_at: Option<DateTime>,
+    pub updated_at: Option<DateTime>,
     pub first_name: Option<String>,
     pub last_name: Option<String>,
     pub state: Option<String>,
@@ -266,7 +457,7 @@ pub struct OrderCustomer {
     pub tags: String,
     pub currency: String,
     pub tax_exemptions: Vec<String>,
-    pub admin_graphql_api_id: String,
+    pub admin_graphql_api_id: Gid,
     pub default_address: Option<OrderAddress>,
 }
 
@@ -274,52 +465,450 @@ pub struct OrderCustomer {
 pub struct OrderConsent {
     pub state: Option<String>,
     pub opt_in_level: Option<String>,
-    pub consent_updated_at: Option<String>,
+    pub consent_updated_at: Option<DateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrderDiscountApplication {
+    #[serde(rename = "type")]
+    pub _type: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub code: Option<String>,
+    pub value: Decimal,
+    pub value_type: String,
+    pub allocation_method: String,
+    pub target_selection: String,
+    pub target_type: String,
 }
 
+/// Part of a discount application allocated to a line item or a shipping line
 #[derive(Debug, Serialize, Deserialize)]
-pub struct OrderDiscountApplication {}
+pub struct OrderDiscountAllocation {
+    pub amount: Decimal,
+    pub amount_set: Option<OrderPriceSet>,
+    /// Index of the discount application in `Order.discount_applications`
+    pub discount_application_index: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrderFulfillment {
+    pub id: u64,
+    pub admin_graphql_api_id: Option<Gid>,
+    pub order_id: u64,
+    pub name: Option<String>,
+    pub status: String,
+    pub service: Option<String>,
+    pub shipment_status: Option<String>,
+    pub location_id: Option<u64>,
+    pub tracking_company: Option<String>,
+    pub tracking_number: Option<String>,
+    #[serde(default)]
+    pub tracking_numbers: Vec<String>,
+    pub tracking_url: Option<String>,
+    #[serde(default)]
+    pub tracking_urls: Vec<String>,
+    pub receipt: Option<OrderFulfillmentReceipt>,
+    #[serde(default)]
+    pub line_items: Vec<OrderLineItem>,
+    pub created_at: DateTime,
+    pub updated_at: Option<DateTime>,
+}
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct OrderFulfillment {}
+pub struct OrderFulfillmentReceipt {
+    pub testcase: Option<bool>,
+    pub authorization: Option<String>,
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OrderLineItem {
     pub id: u64,
-    pub admin_graphql_api_id: Option<String>,
+    pub admin_graphql_api_id: Option<Gid>,
     pub variant_id: Option<u64>,
     pub quantity: i32,
-    pub price: String,
+    pub price: Decimal,
     pub grams: i32,
     pub name: String,
     pub title: String,
     pub variant_title: Option<String>,
     pub sku: Option<String>,
     pub variant_inventory_management: Option<String>,
-    pub product_id: u64,
+    /// `None` for custom line items and deleted products
+    pub product_id: Option<u64>,
     pub fulfillment_service: String,
     pub product_exists: bool,
     pub taxable: bool,
-    pub total_discount: String,
+    pub total_discount: Decimal,
     pub fulfillment_status: Option<String>,
+    #[serde(default)]
+    pub tax_lines: Vec<OrderTaxLine>,
+    #[serde(default)]
+    pub discount_allocations: Vec<OrderDiscountAllocation>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct OrderPaymentTerms {}
+pub struct OrderPaymentTerms {
+    pub id: u64,
+    pub payment_terms_name: String,
+    pub payment_terms_type: String,
+    pub due_in_days: Option<u32>,
+    #[serde(default)]
+    pub payment_schedules: Vec<OrderPaymentSchedule>,
+    pub created_at: Option<DateTime>,
+    pub updated_at: Option<DateTime>,
+}
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct OrderRefund {}
+pub struct OrderPaymentSchedule {
+    pub id: Option<u64>,
+    pub amount: Decimal,
+    pub currency: String,
+    pub issued_at: Option<DateTime>,
+    pub due_at: Option<DateTime>,
+    pub completed_at: Option<DateTime>,
+    pub expected_payment_method: Option<String>,
+    pub created_at: Option<DateTime>,
+    pub updated_at: Option<DateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrderRefund {
+    pub id: u64,
+    pub admin_graphql_api_id: Option<Gid>,
+    pub order_id: u64,
+    pub note: Option<String>,
+    pub restock: Option<bool>,
+    pub user_id: Option<u64>,
+    pub total_duties_set: Option<OrderDutiesSet>,
+    #[serde(default)]
+    pub refund_line_items: Vec<OrderRefundLineItem>,
+    #[serde(default)]
+    pub transactions: Vec<OrderTransaction>,
+    #[serde(default)]
+    pub order_adjustments: Vec<OrderAdjustment>,
+    pub created_at: DateTime,
+    pub processed_at: Option<DateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrderRefundLineItem {
+    pub id: u64,
+    pub line_item_id: u64,
+    pub location_id: Option<u64>,
+    pub quantity: i32,
+    pub restock_type: String,
+    pub subtotal: Decimal,
+    pub subtotal_set: Option<OrderPriceSet>,
+    pub total_tax: Decimal,
+    pub total_tax_set: Option<OrderPriceSet>,
+    pub line_item: Option<OrderLineItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrderTransaction {
+    pub id: u64,
+    pub admin_graphql_api_id: Option<Gid>,
+    pub order_id: Option<u64>,
+    pub parent_id: Option<u64>,
+    pub kind: String,
+    pub status: String,
+    pub gateway: Option<String>,
+    pub message: Option<String>,
+    pub amount: Decimal,
+    pub currency: Option<String>,
+    pub authorization: Option<String>,
+    pub error_code: Option<String>,
+    pub source_name: Option<String>,
+    pub payment_id: Option<String>,
+    pub location_id: Option<u64>,
+    pub user_id: Option<u64>,
+    pub device_id: Option<u64>,
+    #[serde(default)]
+    pub test: bool,
+    pub receipt: Option<Value>,
+    pub created_at: Option<DateTime>,
+    pub processed_at: Option<DateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrderAdjustment {
+    pub id: u64,
+    pub order_id: u64,
+    pub refund_id: u64,
+    pub kind: String,
+    pub reason: Option<String>,
+    pub amount: Decimal,
+    pub amount_set: Option<OrderPriceSet>,
+    pub tax_amount: Decimal,
+    pub tax_amount_set: Option<OrderPriceSet>,
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OrderShippingLine {
     pub id: u64,
     pub title: String,
-    pub price: String,
+    pub price: Decimal,
     pub code: Option<String>,
     pub source: String,
     pub phone: Option<String>,
     pub requested_fulfillment_service_id: Option<String>,
     pub delivery_category: Option<String>,
     pub carrier_identifier: Option<String>,
-    pub discounted_price: String,
+    pub discounted_price: Decimal,
+    #[serde(default)]
+    pub tax_lines: Vec<OrderTaxLine>,
+    #[serde(default)]
+    pub discount_allocations: Vec<OrderDiscountAllocation>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Product {
+    pub id: u64,
+    pub title: String,
+    pub body_html: Option<String>,
+    pub vendor: Option<String>,
+    pub product_type: Option<String>,
+    pub handle: String,
+    pub created_at: Option<DateTime>,
+    pub updated_at: Option<DateTime>,
+    pub published_at: Option<DateTime>,
+    pub template_suffix: Option<String>,
+    pub published_scope: Option<String>,
+    pub tags: String,
+    pub status: Option<String>,
+    pub admin_graphql_api_id: Gid,
+    #[serde(default)]
+    pub variants: Vec<ProductVariant>,
+    #[serde(default)]
+    pub options: Vec<ProductOption>,
+    #[serde(default)]
+    pub images: Vec<ProductImage>,
+    pub image: Option<ProductImage>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProductVariant {
+    pub id: u64,
+    pub product_id: u64,
+    pub title: String,
+    pub price: Decimal,
+    pub compare_at_price: Option<Decimal>,
+    pub sku: Option<String>,
+    pub barcode: Option<String>,
+    pub position: Option<i32>,
+    pub inventory_policy: Option<String>,
+    pub inventory_management: Option<String>,
+    pub inventory_item_id: Option<u64>,
+    pub inventory_quantity: Option<i64>,
+    pub old_inventory_quantity: Option<i64>,
+    pub fulfillment_service: Option<String>,
+    pub option1: Option<String>,
+    pub option2: Option<String>,
+    pub option3: Option<String>,
+    pub taxable: Option<bool>,
+    pub requires_shipping: Option<bool>,
+    pub grams: Option<i64>,
+    pub weight: Option<f64>,
+    pub weight_unit: Option<String>,
+    pub image_id: Option<u64>,
+    pub created_at: Option<DateTime>,
+    pub updated_at: Option<DateTime>,
+    pub admin_graphql_api_id: Gid,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProductOption {
+    pub id: u64,
+    pub product_id: u64,
+    pub name: String,
+    pub position: i32,
+    pub values: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProductImage {
+    pub id: u64,
+    pub product_id: u64,
+    pub position: i32,
+    pub alt: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub src: String,
+    #[serde(default)]
+    pub variant_ids: Vec<u64>,
+    pub created_at: Option<DateTime>,
+    pub updated_at: Option<DateTime>,
+    pub admin_graphql_api_id: Option<Gid>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Collection {
+    pub id: u64,
+    pub handle: String,
+    pub title: String,
+    pub body_html: Option<String>,
+    pub sort_order: Option<String>,
+    pub template_suffix: Option<String>,
+    pub published_scope: Option<String>,
+    pub published_at: Option<DateTime>,
+    pub updated_at: Option<DateTime>,
+    pub admin_graphql_api_id: Gid,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Shop {
+    pub id: u64,
+    pub name: String,
+    pub email: Option<String>,
+    pub customer_email: Option<String>,
+    pub shop_owner: Option<String>,
+    pub phone: Option<String>,
+    pub domain: Option<String>,
+    pub myshopify_domain: Option<String>,
+    pub address1: Option<String>,
+    pub address2: Option<String>,
+    pub city: Option<String>,
+    pub zip: Option<String>,
+    pub province: Option<String>,
+    pub province_code: Option<String>,
+    pub country: Option<String>,
+    pub country_code: Option<String>,
+    pub country_name: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub primary_locale: Option<String>,
+    pub primary_location_id: Option<u64>,
+    pub currency: Option<String>,
+    #[serde(default)]
+    pub enabled_presentment_currencies: Vec<String>,
+    pub money_format: Option<String>,
+    pub money_with_currency_format: Option<String>,
+    pub timezone: Option<String>,
+    pub iana_timezone: Option<String>,
+    pub weight_unit: Option<String>,
+    pub taxes_included: Option<bool>,
+    pub tax_shipping: Option<bool>,
+    pub plan_name: Option<String>,
+    pub plan_display_name: Option<String>,
+    pub password_enabled: Option<bool>,
+    pub multi_location_enabled: Option<bool>,
+    pub created_at: Option<DateTime>,
+    pub updated_at: Option<DateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Cart {
+    pub id: String,
+    pub token: String,
+    pub note: Option<String>,
+    #[serde(default)]
+    pub line_items: Vec<CartLineItem>,
+    pub created_at: Option<DateTime>,
+    pub updated_at: Option<DateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CartLineItem {
+    pub id: u64,
+    pub key: String,
+    pub product_id: Option<u64>,
+    pub variant_id: Option<u64>,
+    pub title: String,
+    pub sku: Option<String>,
+    pub vendor: Option<String>,
+    pub quantity: i64,
+    pub grams: Option<i64>,
+    pub gift_card: Option<bool>,
+    pub taxable: Option<bool>,
+    pub price: Decimal,
+    pub original_price: Option<Decimal>,
+    pub discounted_price: Option<Decimal>,
+    pub line_price: Option<Decimal>,
+    pub original_line_price: Option<Decimal>,
+    pub total_discount: Option<Decimal>,
+    pub properties: Option<Value>,
+    #[serde(default)]
+    pub discounts: Vec<Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Checkout {
+    pub id: u64,
+    pub token: String,
+    pub cart_token: Option<String>,
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub gateway: Option<String>,
+    pub buyer_accepts_marketing: Option<bool>,
+    pub abandoned_checkout_url: Option<String>,
+    pub landing_site: Option<String>,
+    pub referring_site: Option<String>,
+    pub source_name: Option<String>,
+    pub note: Option<String>,
+    #[serde(default)]
+    pub note_attributes: Vec<OrderNoteAttribute>,
+    pub currency: Option<String>,
+    pub presentment_currency: Option<String>,
+    pub customer_locale: Option<String>,
+    pub taxes_included: Option<bool>,
+    pub total_weight: Option<u64>,
+    pub subtotal_price: Option<Decimal>,
+    pub total_discounts: Option<Decimal>,
+    pub total_line_items_price: Option<Decimal>,
+    pub total_price: Option<Decimal>,
+    pub total_tax: Option<Decimal>,
+    pub total_duties: Option<Decimal>,
+    #[serde(default)]
+    pub line_items: Vec<CheckoutLineItem>,
+    #[serde(default)]
+    pub tax_lines: Vec<OrderTaxLine>,
+    #[serde(default)]
+    pub discount_codes: Vec<OrderDiscountCode>,
+    pub billing_address: Option<OrderAddress>,
+    pub shipping_address: Option<OrderAddress>,
+    pub customer: Option<OrderCustomer>,
+    pub location_id: Option<u64>,
+    pub user_id: Option<u64>,
+    pub device_id: Option<u64>,
+    pub completed_at: Option<DateTime>,
+    pub closed_at: Option<DateTime>,
+    pub created_at: Option<DateTime>,
+    pub updated_at: Option<DateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CheckoutLineItem {
+    pub key: String,
+    pub product_id: Option<u64>,
+    pub variant_id: Option<u64>,
+    pub title: String,
+    pub variant_title: Option<String>,
+    pub sku: Option<String>,
+    pub vendor: Option<String>,
+    pub quantity: i64,
+    pub grams: Option<i64>,
+    pub gift_card: Option<bool>,
+    pub taxable: Option<bool>,
+    pub requires_shipping: Option<bool>,
+    pub fulfillment_service: Option<String>,
+    pub price: Decimal,
+    pub compare_at_price: Option<Decimal>,
+    pub line_price: Option<Decimal>,
+    #[serde(default)]
+    pub tax_lines: Vec<OrderTaxLine>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Theme {
+    pub id: u64,
+    pub name: String,
+    pub role: String,
+    pub theme_store_id: Option<u64>,
+    pub previewable: Option<bool>,
+    pub processing: Option<bool>,
+    pub created_at: Option<DateTime>,
+    pub updated_at: Option<DateTime>,
+    pub admin_graphql_api_id: Option<Gid>,
 }
@@ -0,0 +1,389 @@
+use super::dedup::{self, WebhookClaim, WebhookDedupStore};
+use super::frameworks::ShopifyWebhook;
+use super::topic::WebhookTopic;
+use crate::graphql::types::DateTime;
+use crate::{Shopify, ShopifyAPIError};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::sync::Mutex;
+
+pub const HMAC_HEADER: &str = "x-shopify-hmac-sha256";
+pub const TOPIC_HEADER: &str = "x-shopify-topic";
+pub const SHOP_DOMAIN_HEADER: &str = "x-shopify-shop-domain";
+pub const WEBHOOK_ID_HEADER: &str = "x-shopify-webhook-id";
+pub const API_VERSION_HEADER: &str = "x-shopify-api-version";
+pub const EVENT_ID_HEADER: &str = "x-shopify-event-id";
+pub const TRIGGERED_AT_HEADER: &str = "x-shopify-triggered-at";
+
+/// A webhook request as received by any HTTP framework: its headers and raw body
+/// # Example
+/// ```
+/// use shopify_api::webhooks::handler::WebhookRequest;
+///
+/// let request = WebhookRequest::new(vec![("X-Shopify-Topic", "orders/create")], b"{}".to_vec())
+///     .with_header("X-Shopify-Shop-Domain", "myshop.myshopify.com");
+///
+/// assert_eq!(request.header("x-shopify-topic"), Some("orders/create"));
+/// assert_eq!(request.header("X-SHOPIFY-SHOP-DOMAIN"), Some("myshop.myshopify.com"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct WebhookRequest {
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+impl WebhookRequest {
+    pub fn new<K, V>(headers: impl IntoIterator<Item = (K, V)>, body: Vec<u8>) -> WebhookRequest
+    where
+        K: AsRef<str>,
+        V: Into<String>,
+    {
+        WebhookRequest {
+            headers: headers
+                .into_iter()
+                .map(|(name, value)| (name.as_ref().to_ascii_lowercase(), value.into()))
+                .collect(),
+            body,
+        }
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> WebhookRequest {
+        self.headers
+            .insert(name.to_ascii_lowercase(), value.to_string());
+        self
+    }
+
+    /// Get a header value, the name is case insensitive
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(&name.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+}
+
+/// A verified webhook, with its typed payload
+#[derive(Debug)]
+pub struct WebhookEvent {
+    pub topic: WebhookTopic,
+    /// `X-Shopify-Shop-Domain` header
+    pub shop_domain: Option<String>,
+    /// `X-Shopify-Webhook-Id` header
+    pub webhook_id: Option<String>,
+    /// `X-Shopify-API-Version` header
+    pub api_version: Option<String>,
+    /// `X-Shopify-Event-Id` header, shared by the deliveries of the same event
+    pub event_id: Option<String>,
+    /// `X-Shopify-Triggered-At` header
+    pub triggered_at: Option<DateTime>,
+    /// Index of the shared secret which signed the webhook, `0` being the current one
+    pub secret_index: usize,
+    pub payload: ShopifyWebhook,
+}
+
+impl WebhookEvent {
+    /// The key identifying this delivery in a [`WebhookDedupStore`]
+    ///
+    /// The webhook id, shared by the retries of a delivery, or the topic and the event id when
+    /// Shopify did not send it. The event id alone is shared by the webhooks of every topic
+    /// triggered by the same event, so it is never used on its own.
+    pub fn dedup_key(&self) -> Option<String> {
+        match (&self.webhook_id, &self.event_id) {
+            (Some(webhook_id), _) => Some(webhook_id.clone()),
+            (None, Some(event_id)) => Some(format!("{}:{}", self.topic, event_id)),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Why a webhook request was rejected
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    #[error("Missing X-Shopify-Hmac-Sha256 header")]
+    MissingHmac,
+    #[error("Invalid HMAC")]
+    InvalidHmac,
+    #[error("Missing X-Shopify-Topic header")]
+    MissingTopic,
+    #[error("Invalid {topic} payload: {source}")]
+    InvalidPayload {
+        topic: WebhookTopic,
+        source: ShopifyAPIError,
+    },
+}
+
+impl WebhookError {
+    /// The HTTP status code to answer the request with
+    ///
+    /// `401` for a missing or invalid signature, `400` for a request that can not be parsed.
+    pub fn status_code(&self) -> u16 {
+        match self {
+            WebhookError::MissingHmac | WebhookError::InvalidHmac => 401,
+            WebhookError::MissingTopic | WebhookError::InvalidPayload { .. } => 400,
+        }
+    }
+}
+
+/// Function called with every webhook error, see [`crate::ShopifyBuilder::webhook_error_hook`]
+#[derive(Clone)]
+pub struct WebhookErrorHook(Arc<dyn Fn(&WebhookError) + Send + Sync>);
+
+impl WebhookErrorHook {
+    pub fn new(hook: impl Fn(&WebhookError) + Send + Sync + 'static) -> WebhookErrorHook {
+        WebhookErrorHook(Arc::new(hook))
+    }
+}
+
+impl fmt::Debug for WebhookErrorHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("WebhookErrorHook")
+    }
+}
+
+/// How the client handles webhooks it can not process or already processed
+#[derive(Clone, Default)]
+pub(crate) struct WebhookOptions {
+    pub(crate) error_hook: Option<WebhookErrorHook>,
+    pub(crate) raw_fallback: bool,
+    pub(crate) dedup_store: Option<Arc<dyn WebhookDedupStore>>,
+}
+
+impl fmt::Debug for WebhookOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebhookOptions")
+            .field("error_hook", &self.error_hook)
+            .field("raw_fallback", &self.raw_fallback)
+            .field("dedup_store", &self.dedup_store.is_some())
+            .finish()
+    }
+}
+
+/// What a webhook handler did with a request
+///
+/// The framework wrappers answer Shopify with [`WebhookOutcome::status_code`]: `200` when the
+/// delivery was processed or is a duplicate, `409` when another attempt is in progress, `401`
+/// for an invalid signature, `400` for an unparsable payload and `500` when the callback failed.
+/// Shopify retries every delivery which is not answered with a `2xx`.
+#[derive(Debug)]
+pub enum WebhookOutcome {
+    /// The callback processed the webhook
+    Processed,
+    /// The delivery was already processed, the callback was not called
+    Duplicate,
+    /// Another attempt of the delivery is being processed, the callback was not called
+    InProgress,
+    /// The request was rejected before calling the callback
+    Rejected(WebhookError),
+    /// The callback returned an error
+    CallbackFailed(String),
+}
+
+impl WebhookOutcome {
+    /// The HTTP status code to answer the request with
+    pub fn status_code(&self) -> u16 {
+        match self {
+            WebhookOutcome::Processed | WebhookOutcome::Duplicate => 200,
+            WebhookOutcome::InProgress => 409,
+            WebhookOutcome::Rejected(e) => e.status_code(),
+            WebhookOutcome::CallbackFailed(_) => 500,
+        }
+    }
+
+    /// A short body to answer the request with
+    pub fn message(&self) -> &'static str {
+        match self {
+            WebhookOutcome::Processed => "Success",
+            WebhookOutcome::Duplicate => "Duplicate",
+            WebhookOutcome::InProgress => "In progress",
+            WebhookOutcome::Rejected(_) => "Invalid webhook",
+            WebhookOutcome::CallbackFailed(_) => "Callback failed",
+        }
+    }
+}
+
+impl WebhookOptions {
+    fn report(&self, error: &WebhookError) {
+        if let Some(hook) = &self.error_hook {
+            (hook.0)(error);
+        }
+    }
+}
+
+impl Shopify {
+    /// Verify and parse a webhook request, independently of the HTTP framework receiving it
+    ///
+    /// The HMAC of the body is checked against the shared secret before anything is parsed.
+    /// Every error is reported to the error hook of the client. With the raw fallback enabled,
+    /// a payload which does not match its topic is delivered as `ShopifyWebhook::Other`.
+    /// # Example
+    /// ```
+    /// use shopify_api::Shopify;
+    /// use shopify_api::webhooks::handler::WebhookRequest;
+    ///
+    /// let shopify = Shopify::new("myshop", "myapikey", String::from("2024-04"), Some("secret"));
+    /// let request = WebhookRequest::new(
+    ///     vec![
+    ///         ("X-Shopify-Topic", "products/delete"),
+    ///         ("X-Shopify-Hmac-Sha256", "forged"),
+    ///     ],
+    ///     br#"{"id": 1}"#.to_vec(),
+    /// );
+    ///
+    /// let error = shopify.process_webhook(&request).unwrap_err();
+    /// assert_eq!(error.status_code(), 401);
+    /// ```
+    /// # Errors
+    /// This function returns a [`WebhookError`] if the signature is missing or invalid, or if
+    /// the topic or the payload can not be parsed.
+    pub fn process_webhook(&self, request: &WebhookRequest) -> Result<WebhookEvent, WebhookError> {
+        let result = self.read_webhook(request);
+
+        if let Err(error) = &result {
+            self.webhook_options.report(error);
+        }
+
+        result
+    }
+
+    fn read_webhook(&self, request: &WebhookRequest) -> Result<WebhookEvent, WebhookError> {
+        let hmac = request
+            .header(HMAC_HEADER)
+            .ok_or(WebhookError::MissingHmac)?;
+
+        let secret_index = self
+            .verify_hmac_secret(request.body(), hmac)
+            .ok_or(WebhookError::InvalidHmac)?;
+
+        if secret_index > 0 {
+            log::debug!("Webhook signed with the shared secret {}", secret_index);
+        }
+
+        let topic = WebhookTopic::from(
+            request
+                .header(TOPIC_HEADER)
+                .ok_or(WebhookError::MissingTopic)?,
+        );
+
+        log::debug!(
+            "Received webhook topic: {} with body {}",
+            topic,
+            String::from_utf8_lossy(request.body())
+        );
+
+        let payload = match ShopifyWebhook::parse(&topic, request.body()) {
+            Ok(payload) => payload,
+            Err(source) => {
+                let error = WebhookError::InvalidPayload {
+                    topic: topic.clone(),
+                    source,
+                };
+
+                let raw = match self.webhook_options.raw_fallback {
+                    true => serde_json::from_slice::<Value>(request.body()).ok(),
+                    false => None,
+                };
+
+                match raw {
+                    Some(raw) => {
+                        self.webhook_options.report(&error);
+                        ShopifyWebhook::Other((topic.to_string(), raw))
+                    }
+                    None => return Err(error),
+                }
+            }
+        };
+
+        Ok(WebhookEvent {
+            topic,
+            shop_domain: request.header(SHOP_DOMAIN_HEADER).map(str::to_string),
+            webhook_id: request.header(WEBHOOK_ID_HEADER).map(str::to_string),
+            api_version: request.header(API_VERSION_HEADER).map(str::to_string),
+            event_id: request.header(EVENT_ID_HEADER).map(str::to_string),
+            triggered_at: request
+                .header(TRIGGERED_AT_HEADER)
+                .and_then(|triggered_at| triggered_at.parse().ok()),
+            secret_index,
+            payload,
+        })
+    }
+
+    /// Process a webhook request and dispatch it to `callback`, the flow shared by the
+    /// framework wrappers
+    ///
+    /// The request is verified and parsed with [`Shopify::process_webhook`] and the delivery is
+    /// claimed in the dedup store. Deliveries already processed are skipped, deliveries being
+    /// processed by another attempt are answered with `409` so that Shopify retries them. The
+    /// claim is completed when the callback succeeds and released when it fails, so that Shopify's
+    /// next attempt is processed.
+    ///
+    /// `callback` is called with the [`WebhookEvent`], the shared `Shopify` and `extra_data`, the
+    /// returned [`WebhookOutcome`] gives the status to answer the request with.
+    pub async fn dispatch_webhook<F, Fut, T>(
+        shopify: &Arc<Mutex<Shopify>>,
+        request: &WebhookRequest,
+        extra_data: T,
+        callback: F,
+    ) -> WebhookOutcome
+    where
+        F: FnOnce(WebhookEvent, Arc<Mutex<Shopify>>, T) -> Fut,
+        Fut: Future<Output = Result<(), String>>,
+    {
+        // The client is not locked while the dedup store is awaited
+        let (event, store) = {
+            let shopify = shopify.lock().await;
+            let store = shopify.webhook_options.dedup_store.clone();
+
+            (shopify.process_webhook(request), store)
+        };
+
+        let event = match event {
+            Ok(event) => event,
+            Err(e) => {
+                log::info!("Rejected webhook: {}", e);
+                return WebhookOutcome::Rejected(e);
+            }
+        };
+
+        let claim = store.zip(event.dedup_key());
+
+        if let Some((store, key)) = &claim {
+            match dedup::claim(store.as_ref(), key).await {
+                WebhookClaim::Claimed => {}
+                WebhookClaim::InProgress => {
+                    log::debug!("Webhook {} is already in progress", key);
+                    return WebhookOutcome::InProgress;
+                }
+                WebhookClaim::Processed => {
+                    log::debug!("Skipped duplicate webhook {}", key);
+                    return WebhookOutcome::Duplicate;
+                }
+            }
+        }
+
+        match callback(event, shopify.clone(), extra_data).await {
+            Ok(_) => {
+                if let Some((store, key)) = &claim {
+                    dedup::complete(store.as_ref(), key).await;
+                }
+
+                WebhookOutcome::Processed
+            }
+            Err(e) => {
+                log::info!("Webhook callback failed: {}", e);
+
+                if let Some((store, key)) = &claim {
+                    dedup::release(store.as_ref(), key).await;
+                }
+
+                WebhookOutcome::CallbackFailed(e)
+            }
+        }
+    }
+}
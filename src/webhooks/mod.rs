@@ -1,13 +1,18 @@
+pub mod dedup;
 pub mod frameworks;
+pub mod handler;
+pub mod topic;
 pub mod verify;
 pub mod webhook;
+use crate::Gid;
 use serde::{Deserialize, Serialize};
+use topic::WebhookTopic;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ShopifyWebhook {
     id: u64,
     address: String,
-    topic: String,
+    topic: WebhookTopic,
     created_at: String,
     updated_at: String,
     format: String,
@@ -16,3 +21,10 @@ pub struct ShopifyWebhook {
     api_version: String,
     private_metafield_namespaces: Vec<String>,
 }
+
+impl ShopifyWebhook {
+    /// Get the GraphQL global ID of this webhook subscription
+    pub fn gid(&self) -> Gid {
+        Gid::from_rest_id("WebhookSubscription", self.id)
+    }
+}
@@ -0,0 +1,219 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{convert::Infallible, fmt, str::FromStr};
+
+// https://shopify.dev/docs/api/admin-rest/2024-04/resources/webhook#event-topics
+macro_rules! webhook_topics {
+    ($($variant:ident => $topic:literal,)*) => {
+        /// A webhook event topic, as sent in the `X-Shopify-Topic` header
+        ///
+        /// Topics unknown to this version of the crate are kept in `Other`.
+        /// # Example
+        /// ```
+        /// use shopify_api::webhooks::topic::WebhookTopic;
+        ///
+        /// let topic: WebhookTopic = "orders/create".parse().unwrap();
+        /// assert_eq!(topic, WebhookTopic::OrdersCreate);
+        /// assert_eq!(topic.to_string(), "orders/create");
+        ///
+        /// let topic: WebhookTopic = "something/new".parse().unwrap();
+        /// assert_eq!(topic, WebhookTopic::Other("something/new".to_string()));
+        /// ```
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum WebhookTopic {
+            $($variant,)*
+            Other(String),
+        }
+
+        impl WebhookTopic {
+            /// Get the topic string, e.g. `orders/create`
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(WebhookTopic::$variant => $topic,)*
+                    WebhookTopic::Other(topic) => topic.as_str(),
+                }
+            }
+        }
+
+        impl FromStr for WebhookTopic {
+            type Err = Infallible;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Ok(match s {
+                    $($topic => WebhookTopic::$variant,)*
+                    other => WebhookTopic::Other(other.to_string()),
+                })
+            }
+        }
+    };
+}
+
+webhook_topics! {
+    AppUninstalled => "app/uninstalled",
+    AppPurchasesOneTimeUpdate => "app_purchases_one_time/update",
+    AppSubscriptionsApproachingCappedAmount => "app_subscriptions/approaching_capped_amount",
+    AppSubscriptionsUpdate => "app_subscriptions/update",
+    BulkOperationsFinish => "bulk_operations/finish",
+    CartsCreate => "carts/create",
+    CartsUpdate => "carts/update",
+    CheckoutsCreate => "checkouts/create",
+    CheckoutsDelete => "checkouts/delete",
+    CheckoutsUpdate => "checkouts/update",
+    CollectionListingsAdd => "collection_listings/add",
+    CollectionListingsRemove => "collection_listings/remove",
+    CollectionListingsUpdate => "collection_listings/update",
+    CollectionsCreate => "collections/create",
+    CollectionsDelete => "collections/delete",
+    CollectionsUpdate => "collections/update",
+    CompaniesCreate => "companies/create",
+    CompaniesDelete => "companies/delete",
+    CompaniesUpdate => "companies/update",
+    CustomerGroupsCreate => "customer_groups/create",
+    CustomerGroupsDelete => "customer_groups/delete",
+    CustomerGroupsUpdate => "customer_groups/update",
+    CustomerPaymentMethodsCreate => "customer_payment_methods/create",
+    CustomerPaymentMethodsRevoke => "customer_payment_methods/revoke",
+    CustomerPaymentMethodsUpdate => "customer_payment_methods/update",
+    CustomersCreate => "customers/create",
+    CustomersDataRequest => "customers/data_request",
+    CustomersDelete => "customers/delete",
+    CustomersDisable => "customers/disable",
+    CustomersEnable => "customers/enable",
+    CustomersMerge => "customers/merge",
+    CustomersRedact => "customers/redact",
+    CustomersUpdate => "customers/update",
+    CustomersEmailMarketingConsentUpdate => "customers_email_marketing_consent/update",
+    CustomersMarketingConsentUpdate => "customers_marketing_consent/update",
+    DisputesCreate => "disputes/create",
+    DisputesUpdate => "disputes/update",
+    DomainsCreate => "domains/create",
+    DomainsDestroy => "domains/destroy",
+    DomainsUpdate => "domains/update",
+    DraftOrdersCreate => "draft_orders/create",
+    DraftOrdersDelete => "draft_orders/delete",
+    DraftOrdersUpdate => "draft_orders/update",
+    FulfillmentEventsCreate => "fulfillment_events/create",
+    FulfillmentEventsDelete => "fulfillment_events/delete",
+    FulfillmentOrdersCancelled => "fulfillment_orders/cancelled",
+    FulfillmentOrdersFulfillmentRequestAccepted => "fulfillment_orders/fulfillment_request_accepted",
+    FulfillmentOrdersFulfillmentRequestRejected => "fulfillment_orders/fulfillment_request_rejected",
+    FulfillmentOrdersFulfillmentRequestSubmitted => "fulfillment_orders/fulfillment_request_submitted",
+    FulfillmentOrdersHoldReleased => "fulfillment_orders/hold_released",
+    FulfillmentOrdersMoved => "fulfillment_orders/moved",
+    FulfillmentOrdersOrderRoutingComplete => "fulfillment_orders/order_routing_complete",
+    FulfillmentOrdersPlacedOnHold => "fulfillment_orders/placed_on_hold",
+    FulfillmentOrdersRescheduled => "fulfillment_orders/rescheduled",
+    FulfillmentOrdersScheduledFulfillmentOrderReady => "fulfillment_orders/scheduled_fulfillment_order_ready",
+    FulfillmentsCreate => "fulfillments/create",
+    FulfillmentsUpdate => "fulfillments/update",
+    InventoryItemsCreate => "inventory_items/create",
+    InventoryItemsDelete => "inventory_items/delete",
+    InventoryItemsUpdate => "inventory_items/update",
+    InventoryLevelsConnect => "inventory_levels/connect",
+    InventoryLevelsDisconnect => "inventory_levels/disconnect",
+    InventoryLevelsUpdate => "inventory_levels/update",
+    LocalesCreate => "locales/create",
+    LocalesUpdate => "locales/update",
+    LocationsActivate => "locations/activate",
+    LocationsCreate => "locations/create",
+    LocationsDeactivate => "locations/deactivate",
+    LocationsDelete => "locations/delete",
+    LocationsUpdate => "locations/update",
+    MarketsCreate => "markets/create",
+    MarketsDelete => "markets/delete",
+    MarketsUpdate => "markets/update",
+    MetaobjectsCreate => "metaobjects/create",
+    MetaobjectsDelete => "metaobjects/delete",
+    MetaobjectsUpdate => "metaobjects/update",
+    OrderTransactionsCreate => "order_transactions/create",
+    OrdersCancelled => "orders/cancelled",
+    OrdersCreate => "orders/create",
+    OrdersDelete => "orders/delete",
+    OrdersEdited => "orders/edited",
+    OrdersFulfilled => "orders/fulfilled",
+    OrdersPaid => "orders/paid",
+    OrdersPartiallyFulfilled => "orders/partially_fulfilled",
+    OrdersUpdated => "orders/updated",
+    PaymentTermsCreate => "payment_terms/create",
+    PaymentTermsDelete => "payment_terms/delete",
+    PaymentTermsUpdate => "payment_terms/update",
+    ProductListingsAdd => "product_listings/add",
+    ProductListingsRemove => "product_listings/remove",
+    ProductListingsUpdate => "product_listings/update",
+    ProductsCreate => "products/create",
+    ProductsDelete => "products/delete",
+    ProductsUpdate => "products/update",
+    ProfilesCreate => "profiles/create",
+    ProfilesDelete => "profiles/delete",
+    ProfilesUpdate => "profiles/update",
+    RefundsCreate => "refunds/create",
+    ReturnsApprove => "returns/approve",
+    ReturnsCancel => "returns/cancel",
+    ReturnsClose => "returns/close",
+    ReturnsDecline => "returns/decline",
+    ReturnsReopen => "returns/reopen",
+    ReturnsRequest => "returns/request",
+    SellingPlanGroupsCreate => "selling_plan_groups/create",
+    SellingPlanGroupsDelete => "selling_plan_groups/delete",
+    SellingPlanGroupsUpdate => "selling_plan_groups/update",
+    ShopRedact => "shop/redact",
+    ShopUpdate => "shop/update",
+    SubscriptionBillingAttemptsChallenged => "subscription_billing_attempts/challenged",
+    SubscriptionBillingAttemptsFailure => "subscription_billing_attempts/failure",
+    SubscriptionBillingAttemptsSuccess => "subscription_billing_attempts/success",
+    SubscriptionContractsCreate => "subscription_contracts/create",
+    SubscriptionContractsUpdate => "subscription_contracts/update",
+    TenderTransactionsCreate => "tender_transactions/create",
+    ThemesCreate => "themes/create",
+    ThemesDelete => "themes/delete",
+    ThemesPublish => "themes/publish",
+    ThemesUpdate => "themes/update",
+    VariantsInStock => "variants/in_stock",
+    VariantsOutOfStock => "variants/out_of_stock",
+}
+
+impl fmt::Display for WebhookTopic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&str> for WebhookTopic {
+    fn from(topic: &str) -> Self {
+        match topic.parse() {
+            Ok(topic) => topic,
+            Err(infallible) => match infallible {},
+        }
+    }
+}
+
+impl From<String> for WebhookTopic {
+    fn from(topic: String) -> Self {
+        WebhookTopic::from(topic.as_str())
+    }
+}
+
+impl From<&String> for WebhookTopic {
+    fn from(topic: &String) -> Self {
+        WebhookTopic::from(topic.as_str())
+    }
+}
+
+impl Serialize for WebhookTopic {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for WebhookTopic {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let topic = String::deserialize(deserializer)?;
+
+        Ok(WebhookTopic::from(topic.as_str()))
+    }
+}
@@ -5,28 +5,54 @@ use hmac::{Hmac, Mac};
 use sha2::Sha256;
 
 impl Shopify {
+    /// Verify the `X-Shopify-Hmac-Sha256` header of a webhook against any of the shared secrets
     pub fn verify_hmac(&self, data: &[u8], hmac_header: &str) -> bool {
-        if let Some(secret) = &self.shared_secret {
-            let mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes());
-
-            if mac.is_err() {
-                log::info!("Failed to create hmac with {:?}", mac.err().unwrap());
-                return false;
-            }
-
-            let mut mac = mac.unwrap();
-
-            mac.update(data);
-            let result = mac.finalize();
-            let calculated_hmac = BASE64_STANDARD.encode(result.into_bytes());
-
-            log::debug!("Calculated HMAC: {}", calculated_hmac);
+        self.verify_hmac_secret(data, hmac_header).is_some()
+    }
 
-            return calculated_hmac == hmac_header;
+    /// Verify the `X-Shopify-Hmac-Sha256` header of a webhook, returning the index of the
+    /// shared secret which signed it
+    ///
+    /// The secrets are tried in order and compared in constant time.
+    /// # Example
+    /// ```
+    /// use shopify_api::*;
+    ///
+    /// let shopify = Shopify::builder("myshop", "myapikey", "2024-04")
+    ///     .shared_secrets(["newsharedsecret", "oldsharedsecret"])
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// // Signed with the previous secret
+    /// let hmac = "7OGXsqoJQCQ5eAgXe4mrLyb0ftLin2pxfuwWs6kW1ms=";
+    /// assert_eq!(shopify.verify_hmac_secret(b"{}", hmac), Some(1));
+    /// assert_eq!(shopify.verify_hmac_secret(b"{\"id\":1}", hmac), None);
+    /// ```
+    pub fn verify_hmac_secret(&self, data: &[u8], hmac_header: &str) -> Option<usize> {
+        if self.shared_secrets.is_empty() {
+            log::info!("No shared secret found");
+            return None;
         }
 
-        log::info!("No shared secret found");
-
-        false
+        let hmac = match BASE64_STANDARD.decode(hmac_header.trim()) {
+            Ok(hmac) => hmac,
+            Err(e) => {
+                log::info!("Invalid HMAC header: {}", e);
+                return None;
+            }
+        };
+
+        self.shared_secrets.iter().position(|secret| {
+            match Hmac::<Sha256>::new_from_slice(secret.as_bytes()) {
+                Ok(mut mac) => {
+                    mac.update(data);
+                    mac.verify_slice(&hmac).is_ok()
+                }
+                Err(e) => {
+                    log::info!("Failed to create hmac with {:?}", e);
+                    false
+                }
+            }
+        })
     }
 }
@@ -1,22 +1,49 @@
-use super::ShopifyWebhook;
+use super::{topic::WebhookTopic, ShopifyWebhook};
 use crate::{rest::ShopifyAPIRestType, utils::ReadJsonTreeSteps, Shopify, ShopifyAPIError};
+use futures::TryStreamExt;
 use serde_json::json;
 use std::collections::HashMap;
 
 impl Shopify {
     pub async fn list_webhooks(&self) -> Result<Vec<ShopifyWebhook>, ShopifyAPIError> {
-        self.rest_query::<Vec<ShopifyWebhook>>(
-            &ShopifyAPIRestType::Get("webhooks.json", &HashMap::new()),
-            &Some(vec![ReadJsonTreeSteps::Key("webhooks")]),
+        let params = HashMap::from([("limit", "250")]);
+
+        self.rest_paginate::<ShopifyWebhook>(
+            "webhooks.json",
+            &params,
+            vec![ReadJsonTreeSteps::Key("webhooks")],
         )
+        .try_collect()
         .await
     }
+    /// Subscribe `address` to a webhook topic
+    ///
+    /// The topic can be a [`WebhookTopic`] or its name.
+    /// # Example
+    /// ```no_run
+    /// use shopify_api::*;
+    /// use shopify_api::webhooks::topic::WebhookTopic;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let shopify = Shopify::new("myshop", "myapikey", String::from("2024-04"), None);
+    ///     let address = "https://example.com/webhooks";
+    ///
+    ///     shopify.add_webhook(address, WebhookTopic::OrdersCreate, "json").await.unwrap();
+    ///     shopify.add_webhook(address, "orders/paid", "json").await.unwrap();
+    ///
+    ///     let topic = String::from("orders/updated");
+    ///     shopify.add_webhook(address, &topic, "json").await.unwrap();
+    ///     shopify.add_webhook(address, topic, "json").await.unwrap();
+    /// }
+    /// ```
     pub async fn add_webhook(
         &self,
         address: &str,
-        topic: &str,
+        topic: impl Into<WebhookTopic>,
         format: &str,
     ) -> Result<ShopifyWebhook, ShopifyAPIError> {
+        let topic: WebhookTopic = topic.into();
         let webhook_data = json!({
             "webhook": {
                 "address": address,
@@ -77,7 +104,9 @@ impl Shopify {
         for (address, topic, format) in desired_webhooks {
             let mut webhook_found = false;
             for webhook in &existing_webhooks {
-                if webhook.address == address && webhook.topic == topic && webhook.format == format
+                if webhook.address == address
+                    && webhook.topic.as_str() == topic
+                    && webhook.format == format
                 {
                     webhook_found = true;
                     webhooks_treated.push(webhook.id);
@@ -0,0 +1,164 @@
+#![cfg(all(feature = "webhooks", feature = "actix-wrapper"))]
+
+mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::{test, web, App, HttpResponse};
+use common::{sign, SECRET};
+use shopify_api::webhooks::frameworks::actix::VerifiedWebhook;
+use shopify_api::webhooks::frameworks::ShopifyWebhook;
+use shopify_api::Shopify;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+async fn products_delete(webhook: VerifiedWebhook) -> HttpResponse {
+    match &webhook.payload {
+        ShopifyWebhook::ProductsDelete(product) => HttpResponse::Ok().body(product.id.to_string()),
+        _ => HttpResponse::NotFound().finish(),
+    }
+}
+
+async fn status(topic: &str, body: &'static [u8], hmac: &str) -> (StatusCode, String) {
+    let shopify = Shopify::new("myshop", "myapikey", String::from("2024-04"), Some(SECRET));
+    let app = test::init_service(
+        App::new()
+            .app_data(Arc::new(Mutex::new(shopify)))
+            .route("/webhooks", web::post().to(products_delete)),
+    )
+    .await;
+
+    let request = test::TestRequest::post()
+        .uri("/webhooks")
+        .insert_header(("X-Shopify-Topic", topic))
+        .insert_header(("X-Shopify-Hmac-Sha256", hmac))
+        .set_payload(body)
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    let status = response.status();
+    let body = test::read_body(response).await;
+
+    (status, String::from_utf8_lossy(&body).to_string())
+}
+
+#[test]
+fn extracts_verified_webhooks() {
+    actix_web::rt::System::new().block_on(async {
+        let body = br#"{"id": 788032119674292922}"#;
+
+        let (code, body) = status("products/delete", body, &sign(body)).await;
+
+        assert_eq!(code, StatusCode::OK);
+        assert_eq!(body, "788032119674292922");
+    });
+}
+
+#[test]
+fn extracts_with_web_data() {
+    actix_web::rt::System::new().block_on(async {
+        let shopify = Shopify::new("myshop", "myapikey", String::from("2024-04"), Some(SECRET));
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(Mutex::new(shopify)))
+                .route("/webhooks", web::post().to(products_delete)),
+        )
+        .await;
+
+        let body = br#"{"id": 788032119674292922}"#;
+        let request = test::TestRequest::post()
+            .uri("/webhooks")
+            .insert_header(("X-Shopify-Topic", "products/delete"))
+            .insert_header(("X-Shopify-Hmac-Sha256", sign(body)))
+            .set_payload(&body[..])
+            .to_request();
+
+        assert_eq!(
+            test::call_service(&app, request).await.status(),
+            StatusCode::OK
+        );
+    });
+}
+
+#[test]
+fn rejects_invalid_signatures() {
+    actix_web::rt::System::new().block_on(async {
+        let body = br#"{"id": 788032119674292922}"#;
+
+        let (code, _) = status("products/delete", body, &sign(b"something else")).await;
+
+        assert_eq!(code, StatusCode::UNAUTHORIZED);
+    });
+}
+
+#[test]
+fn rejects_unparsable_payloads() {
+    actix_web::rt::System::new().block_on(async {
+        let body = br#"{"id": "not a number"}"#;
+
+        let (code, _) = status("products/delete", body, &sign(body)).await;
+
+        assert_eq!(code, StatusCode::BAD_REQUEST);
+    });
+}
+
+#[test]
+fn actix_wrapper_dispatches_to_the_callback() {
+    actix_web::rt::System::new().block_on(async {
+        let shopify = Shopify::new("myshop", "myapikey", String::from("2024-04"), Some(SECRET));
+        let app = test::init_service(App::new().service(Shopify::actix_wrapper(
+            "/webhooks",
+            Arc::new(Mutex::new(shopify)),
+            (),
+            |event, _shopify, _extra| async move {
+                match event.payload {
+                    ShopifyWebhook::ProductsDelete(_) => Ok(()),
+                    _ => Err("unexpected webhook".to_string()),
+                }
+            },
+        )))
+        .await;
+
+        let body = br#"{"id": 788032119674292922}"#;
+        let request = test::TestRequest::post()
+            .uri("/webhooks")
+            .insert_header(("X-Shopify-Topic", "products/delete"))
+            .insert_header(("X-Shopify-Hmac-Sha256", sign(body)))
+            .set_payload(&body[..])
+            .to_request();
+
+        assert_eq!(
+            test::call_service(&app, request).await.status(),
+            StatusCode::OK
+        );
+    });
+}
+
+#[test]
+fn actix_wrapper_accepts_payloads_over_the_default_limit() {
+    actix_web::rt::System::new().block_on(async {
+        let shopify = Shopify::new("myshop", "myapikey", String::from("2024-04"), Some(SECRET));
+        let app = test::init_service(App::new().service(Shopify::actix_wrapper(
+            "/webhooks",
+            Arc::new(Mutex::new(shopify)),
+            (),
+            |_event, _shopify, _extra| async move { Ok(()) },
+        )))
+        .await;
+
+        // Over the 256 KiB default of actix-web
+        let body = format!(
+            r#"{{"id": 788032119674292922, "padding": "{}"}}"#,
+            "a".repeat(512 * 1024)
+        );
+        let request = test::TestRequest::post()
+            .uri("/webhooks")
+            .insert_header(("X-Shopify-Topic", "products/delete"))
+            .insert_header(("X-Shopify-Hmac-Sha256", sign(body.as_bytes())))
+            .set_payload(body)
+            .to_request();
+
+        assert_eq!(
+            test::call_service(&app, request).await.status(),
+            StatusCode::OK
+        );
+    });
+}
@@ -0,0 +1,187 @@
+#![cfg(all(feature = "webhooks", feature = "axum-wrapper"))]
+
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use common::{sign, SECRET};
+use shopify_api::webhooks::dedup::MemoryDedupStore;
+use shopify_api::webhooks::frameworks::ShopifyWebhook;
+use shopify_api::Shopify;
+use std::sync::Arc;
+use tokio::sync::{Mutex, Notify};
+use tower::ServiceExt;
+
+fn app(received: Arc<Mutex<Vec<ShopifyWebhook>>>, fail: bool) -> axum::Router {
+    let shopify = Shopify::new("myshop", "myapikey", String::from("2024-04"), Some(SECRET));
+
+    Shopify::axum_wrapper(
+        "webhooks",
+        Arc::new(Mutex::new(shopify)),
+        received,
+        move |event, _shopify, received| async move {
+            if fail {
+                return Err("callback failed".to_string());
+            }
+
+            received.lock().await.push(event.payload);
+            Ok(())
+        },
+    )
+}
+
+fn request(topic: &str, body: &'static [u8], hmac: &str) -> Request<Body> {
+    Request::post("/webhooks")
+        .header("X-Shopify-Topic", topic)
+        .header("X-Shopify-Hmac-Sha256", hmac)
+        .header("X-Shopify-Shop-Domain", "myshop.myshopify.com")
+        .body(Body::from(body))
+        .unwrap()
+}
+
+fn delivery(body: &'static [u8]) -> Request<Body> {
+    let mut request = request("products/delete", body, &sign(body));
+    request.headers_mut().insert(
+        "x-shopify-webhook-id",
+        "7bd7ba30-1f4d-4d42-a0f5-d8c6b9f8e1d2".parse().unwrap(),
+    );
+    request
+}
+
+#[tokio::test]
+async fn dispatches_verified_webhooks() {
+    let received = Arc::new(Mutex::new(vec![]));
+    let body = br#"{"id": 788032119674292922}"#;
+
+    let response = app(received.clone(), false)
+        .oneshot(request("products/delete", body, &sign(body)))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(matches!(
+        received.lock().await.as_slice(),
+        [ShopifyWebhook::ProductsDelete(product)] if product.id == 788032119674292922
+    ));
+}
+
+#[tokio::test]
+async fn rejects_invalid_signatures() {
+    let received = Arc::new(Mutex::new(vec![]));
+    let body = br#"{"id": 788032119674292922}"#;
+
+    let response = app(received.clone(), false)
+        .oneshot(request("products/delete", body, &sign(b"something else")))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert!(received.lock().await.is_empty());
+}
+
+#[tokio::test]
+async fn rejects_unparsable_payloads() {
+    let received = Arc::new(Mutex::new(vec![]));
+    let body = br#"{"id": "not a number"}"#;
+
+    let response = app(received.clone(), false)
+        .oneshot(request("products/delete", body, &sign(body)))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert!(received.lock().await.is_empty());
+}
+
+#[tokio::test]
+async fn reports_callback_errors() {
+    let received = Arc::new(Mutex::new(vec![]));
+    let body = br#"{"id": 788032119674292922}"#;
+
+    let response = app(received, true)
+        .oneshot(request("products/delete", body, &sign(body)))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[tokio::test]
+async fn skips_duplicate_deliveries() {
+    let received = Arc::new(Mutex::new(vec![]));
+    let shopify = Shopify::builder("myshop", "myapikey", "2024-04")
+        .shared_secret(SECRET)
+        .webhook_dedup_store(MemoryDedupStore::default())
+        .build()
+        .unwrap();
+    let failures = Arc::new(Mutex::new(1));
+
+    let app = Shopify::axum_wrapper(
+        "webhooks",
+        Arc::new(Mutex::new(shopify)),
+        (received.clone(), failures),
+        |event, _shopify, (received, failures)| async move {
+            let mut failures = failures.lock().await;
+            if *failures > 0 {
+                *failures -= 1;
+                return Err("callback failed".to_string());
+            }
+
+            received.lock().await.push(event.payload);
+            Ok(())
+        },
+    );
+
+    let body = br#"{"id": 788032119674292922}"#;
+
+    let statuses = [
+        app.clone().oneshot(delivery(body)).await.unwrap().status(),
+        app.clone().oneshot(delivery(body)).await.unwrap().status(),
+        app.clone().oneshot(delivery(body)).await.unwrap().status(),
+    ];
+
+    assert_eq!(
+        statuses,
+        [
+            StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::OK,
+            StatusCode::OK
+        ]
+    );
+    assert_eq!(received.lock().await.len(), 1);
+}
+
+#[tokio::test]
+async fn retries_deliveries_in_progress() {
+    let shopify = Shopify::builder("myshop", "myapikey", "2024-04")
+        .shared_secret(SECRET)
+        .webhook_dedup_store(MemoryDedupStore::default())
+        .build()
+        .unwrap();
+    let started = Arc::new(Notify::new());
+    let finish = Arc::new(Notify::new());
+
+    let app = Shopify::axum_wrapper(
+        "webhooks",
+        Arc::new(Mutex::new(shopify)),
+        (started.clone(), finish.clone()),
+        |_event, _shopify, (started, finish)| async move {
+            started.notify_one();
+            finish.notified().await;
+            Ok(())
+        },
+    );
+
+    let body = br#"{"id": 788032119674292922}"#;
+    let first = tokio::spawn(app.clone().oneshot(delivery(body)));
+    started.notified().await;
+
+    let retry = app.clone().oneshot(delivery(body)).await.unwrap();
+    assert_eq!(retry.status(), StatusCode::CONFLICT);
+
+    finish.notify_one();
+    assert_eq!(first.await.unwrap().unwrap().status(), StatusCode::OK);
+
+    let retry = app.clone().oneshot(delivery(body)).await.unwrap();
+    assert_eq!(retry.status(), StatusCode::OK);
+}
@@ -0,0 +1,238 @@
+mod common;
+
+use serde_json::{json, Value};
+use shopify_api::graphql::bulk_poll::BulkPollOptions;
+use shopify_api::graphql::bulk_query::{ShopifyBulkConflict, ShopifyBulkStatus};
+use shopify_api::{Gid, ShopifyAPIError};
+use std::time::Duration;
+use wiremock::matchers::body_string_contains;
+use wiremock::MockServer;
+
+const QUERY: &str = "{ products { edges { node { id } } } }";
+
+async fn mount_running_query(server: &MockServer, final_status: &str) {
+    common::graphql()
+        .and(body_string_contains("bulkOperationRunQuery"))
+        .respond_with(common::graphql_data(json!({
+            "bulkOperationRunQuery": {
+                "bulkOperation": null,
+                "userErrors": [{
+                    "field": null,
+                    "message": "A bulk query operation for this app and shop is already in progress.",
+                }],
+            }
+        })))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(server)
+        .await;
+    common::graphql()
+        .and(body_string_contains("bulkOperationRunQuery"))
+        .respond_with(common::graphql_data(json!({
+            "bulkOperationRunQuery": {
+                "bulkOperation": { "id": "gid://shopify/BulkOperation/2", "status": "CREATED" },
+                "userErrors": [],
+            }
+        })))
+        .mount(server)
+        .await;
+    common::graphql()
+        .and(body_string_contains("currentBulkOperation"))
+        .respond_with(common::graphql_data(json!({
+            "currentBulkOperation": { "id": "gid://shopify/BulkOperation/1", "status": "RUNNING" }
+        })))
+        .expect(1)
+        .mount(server)
+        .await;
+    common::graphql()
+        .and(body_string_contains("node(id"))
+        .and(body_string_contains("gid://shopify/BulkOperation/1"))
+        .respond_with(common::graphql_data(json!({
+            "node": { "id": "gid://shopify/BulkOperation/1", "status": final_status }
+        })))
+        .expect(1)
+        .mount(server)
+        .await;
+}
+
+fn poll_options() -> BulkPollOptions {
+    BulkPollOptions::default()
+        .with_interval(Duration::from_millis(10))
+        .with_deadline(Some(Duration::from_secs(5)))
+}
+
+#[tokio::test]
+async fn waits_for_the_running_bulk_query() {
+    let server = MockServer::start().await;
+    mount_running_query(&server, "COMPLETED").await;
+    common::graphql()
+        .and(body_string_contains("bulkOperationCancel"))
+        .respond_with(common::graphql_data(json!({})))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let result = common::shopify(&server)
+        .make_bulk_query_with(QUERY, ShopifyBulkConflict::Wait, poll_options())
+        .await
+        .unwrap();
+
+    let bulk = result.bulk_operation.unwrap();
+    assert_eq!(
+        bulk.id.unwrap().to_string(),
+        "gid://shopify/BulkOperation/2"
+    );
+}
+
+#[tokio::test]
+async fn cancels_the_running_bulk_query() {
+    let server = MockServer::start().await;
+    mount_running_query(&server, "CANCELED").await;
+    common::graphql()
+        .and(body_string_contains("bulkOperationCancel"))
+        .and(body_string_contains("gid://shopify/BulkOperation/1"))
+        .respond_with(common::graphql_data(json!({
+            "bulkOperationCancel": {
+                "bulkOperation": { "id": "gid://shopify/BulkOperation/1", "status": "CANCELING" },
+                "userErrors": [],
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let result = common::shopify(&server)
+        .make_bulk_query_with(QUERY, ShopifyBulkConflict::Cancel, poll_options())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        result.bulk_operation.unwrap().status,
+        ShopifyBulkStatus::Created
+    );
+}
+
+#[tokio::test]
+async fn fails_on_a_running_bulk_query_by_default() {
+    let server = MockServer::start().await;
+    common::graphql()
+        .and(body_string_contains("bulkOperationRunQuery"))
+        .respond_with(common::graphql_data(json!({
+            "bulkOperationRunQuery": {
+                "bulkOperation": null,
+                "userErrors": [{
+                    "field": null,
+                    "message": "A bulk query operation for this app and shop is already in progress.",
+                }],
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let error = common::shopify(&server)
+        .make_bulk_query_with(QUERY, ShopifyBulkConflict::Fail, poll_options())
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, ShopifyAPIError::UserErrors(_)));
+}
+
+/// The fields of `type_name` in the shipped 2024-04 schema
+fn schema_fields(schema: &Value, type_name: &str) -> Vec<Value> {
+    schema["data"]["__schema"]["types"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|schema_type| schema_type["name"] == type_name)
+        .unwrap()["fields"]
+        .as_array()
+        .unwrap()
+        .clone()
+}
+
+/// The named type of a field, unwrapping `NON_NULL` and `LIST`
+fn named_type(field_type: &Value) -> &str {
+    match field_type["name"].as_str() {
+        Some(name) => name,
+        None => named_type(&field_type["ofType"]),
+    }
+}
+
+/// The fields selected inside `userErrors { ... }` of a query document
+fn selected_user_error_fields(document: &str) -> Vec<String> {
+    let selection = document.split("userErrors").nth(1).unwrap();
+    let start = selection.find('{').unwrap() + 1;
+    let end = selection.find('}').unwrap();
+    selection[start..end]
+        .split_whitespace()
+        .map(str::to_string)
+        .collect()
+}
+
+#[tokio::test]
+async fn user_errors_selections_exist_in_the_schema() {
+    let schema: Value =
+        serde_json::from_str(&std::fs::read_to_string("schemas/2024-04.json").unwrap()).unwrap();
+
+    let server = MockServer::start().await;
+    common::graphql()
+        .and(body_string_contains("bulkOperationRunQuery"))
+        .respond_with(common::graphql_data(json!({
+            "bulkOperationRunQuery": {
+                "bulkOperation": { "id": "gid://shopify/BulkOperation/1", "status": "CREATED" },
+                "userErrors": [],
+            }
+        })))
+        .mount(&server)
+        .await;
+    common::graphql()
+        .and(body_string_contains("bulkOperationCancel"))
+        .respond_with(common::graphql_data(json!({
+            "bulkOperationCancel": {
+                "bulkOperation": { "id": "gid://shopify/BulkOperation/1", "status": "CANCELING" },
+                "userErrors": [],
+            }
+        })))
+        .mount(&server)
+        .await;
+
+    let shopify = common::shopify(&server);
+    shopify.make_bulk_query(QUERY).await.unwrap();
+    shopify
+        .cancel_bulk(&Gid::new("BulkOperation", "1"))
+        .await
+        .unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    let payloads = [
+        ("bulkOperationRunQuery", "BulkOperationRunQueryPayload"),
+        ("bulkOperationCancel", "BulkOperationCancelPayload"),
+    ];
+    for (mutation, payload) in payloads {
+        let body: Value = requests
+            .iter()
+            .map(|request| serde_json::from_slice::<Value>(&request.body).unwrap())
+            .find(|body| body["query"].as_str().unwrap().contains(mutation))
+            .unwrap();
+
+        let user_errors = schema_fields(&schema, payload)
+            .into_iter()
+            .find(|field| field["name"] == "userErrors")
+            .unwrap();
+        let user_error_fields: Vec<Value> =
+            schema_fields(&schema, named_type(&user_errors["type"]))
+                .into_iter()
+                .map(|field| field["name"].clone())
+                .collect();
+
+        let selected = selected_user_error_fields(body["query"].as_str().unwrap());
+        assert!(!selected.is_empty());
+        for field in selected {
+            assert!(
+                user_error_fields.contains(&Value::String(field.clone())),
+                "{mutation} selects {field} which is not a field of its userErrors"
+            );
+        }
+    }
+}
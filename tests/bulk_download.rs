@@ -0,0 +1,124 @@
+mod common;
+
+use futures::TryStreamExt;
+use serde_json::{json, Value};
+use shopify_api::graphql::bulk_download::{BulkDownloadOptions, BulkRecord};
+use shopify_api::ShopifyAPIError;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const RESULTS: &[u8] = b"{\"id\":1}\n{\"id\":2}\n{\"id\":3}\n";
+
+async fn download(
+    server: &MockServer,
+    options: BulkDownloadOptions,
+) -> Result<Vec<BulkRecord<Value>>, ShopifyAPIError> {
+    let url = format!("{}/bulk.jsonl", server.uri());
+
+    common::shopify(server)
+        .download_bulk_records(&url, options)
+        .try_collect()
+        .await
+}
+
+fn records(records: &[(u64, u64)]) -> Vec<BulkRecord<Value>> {
+    records
+        .iter()
+        .map(|(offset, id)| BulkRecord {
+            offset: *offset,
+            data: json!({ "id": id }),
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn records_carry_their_end_offset() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/bulk.jsonl"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(RESULTS))
+        .mount(&server)
+        .await;
+
+    let downloaded = download(&server, BulkDownloadOptions::default()).await;
+
+    assert_eq!(downloaded.unwrap(), records(&[(9, 1), (18, 2), (27, 3)]));
+}
+
+#[tokio::test]
+async fn resumes_from_the_offset_with_a_range() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/bulk.jsonl"))
+        .and(header("Range", "bytes=9-"))
+        .respond_with(ResponseTemplate::new(206).set_body_bytes(&RESULTS[9..]))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let downloaded = download(&server, BulkDownloadOptions::default().with_offset(9)).await;
+
+    assert_eq!(downloaded.unwrap(), records(&[(18, 2), (27, 3)]));
+}
+
+#[tokio::test]
+async fn skips_the_downloaded_data_when_the_range_is_ignored() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/bulk.jsonl"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(RESULTS))
+        .mount(&server)
+        .await;
+
+    let downloaded = download(&server, BulkDownloadOptions::default().with_offset(18)).await;
+
+    assert_eq!(downloaded.unwrap(), records(&[(27, 3)]));
+}
+
+#[tokio::test]
+async fn resuming_at_the_end_of_the_file_yields_nothing() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/bulk.jsonl"))
+        .and(header("Range", "bytes=27-"))
+        .respond_with(ResponseTemplate::new(416))
+        .mount(&server)
+        .await;
+
+    let downloaded = download(&server, BulkDownloadOptions::default().with_offset(27)).await;
+
+    assert_eq!(downloaded.unwrap(), vec![]);
+}
+
+#[cfg(feature = "gzip")]
+#[tokio::test]
+async fn gzip_offsets_are_counted_on_the_decompressed_data() {
+    use tokio::io::AsyncReadExt;
+
+    let mut compressed = vec![];
+    async_compression::tokio::bufread::GzipEncoder::new(RESULTS)
+        .read_to_end(&mut compressed)
+        .await
+        .unwrap();
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/bulk.jsonl"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(compressed))
+        .mount(&server)
+        .await;
+
+    let downloaded = download(&server, BulkDownloadOptions::default().with_gzip(true)).await;
+    assert_eq!(downloaded.unwrap(), records(&[(9, 1), (18, 2), (27, 3)]));
+
+    let options = BulkDownloadOptions::default()
+        .with_gzip(true)
+        .with_offset(9);
+    let downloaded = download(&server, options).await;
+    assert_eq!(downloaded.unwrap(), records(&[(18, 2), (27, 3)]));
+
+    let requests = server.received_requests().await.unwrap();
+    assert!(requests
+        .iter()
+        .all(|request| !request.headers.contains_key("range")));
+}
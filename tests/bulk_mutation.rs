@@ -0,0 +1,106 @@
+mod common;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use shopify_api::graphql::bulk_poll::BulkPollOptions;
+use wiremock::matchers::{body_string_contains, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[derive(Debug, Serialize)]
+struct TagsAdd {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagsAddPayload {
+    node: Option<serde_json::Value>,
+}
+
+const RESULTS: &str = r#"{"data":{"tagsAdd":{"node":{"id":"gid://shopify/Product/3"},"userErrors":[]}},"__lineNumber":2}
+{"data":{"tagsAdd":{"node":null,"userErrors":[{"field":["id"],"message":"Product not found"}]}},"__lineNumber":0}
+"#;
+
+#[tokio::test]
+async fn results_are_matched_by_line_number() {
+    let server = MockServer::start().await;
+
+    common::graphql()
+        .and(body_string_contains("stagedUploadsCreate"))
+        .respond_with(common::graphql_data(json!({
+            "stagedUploadsCreate": {
+                "stagedTargets": [{
+                    "url": format!("{}/upload", server.uri()),
+                    "resourceUrl": null,
+                    "parameters": [
+                        { "name": "key", "value": "tmp/bulk_op_vars" },
+                        { "name": "policy", "value": "c2lnbmVk" },
+                    ],
+                }],
+                "userErrors": [],
+            }
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/upload"))
+        .respond_with(ResponseTemplate::new(201))
+        .mount(&server)
+        .await;
+    common::graphql()
+        .and(body_string_contains("bulkOperationRunMutation"))
+        .respond_with(common::graphql_data(json!({
+            "bulkOperationRunMutation": {
+                "bulkOperation": { "id": "gid://shopify/BulkOperation/1", "status": "CREATED" },
+                "userErrors": [],
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    common::graphql()
+        .and(body_string_contains("node(id"))
+        .respond_with(common::graphql_data(json!({
+            "node": {
+                "id": "gid://shopify/BulkOperation/1",
+                "status": "COMPLETED",
+                "url": format!("{}/results.jsonl", server.uri()),
+            }
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/results.jsonl"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(RESULTS))
+        .mount(&server)
+        .await;
+
+    let inputs = (1..=3).map(|id| TagsAdd {
+        id: format!("gid://shopify/Product/{}", id),
+    });
+    let results = common::shopify(&server)
+        .run_bulk_mutation::<_, TagsAddPayload>(
+            "mutation tagsAdd($id: ID!) { tagsAdd(id: $id, tags: [\"sale\"]) { node { id } userErrors { field message } } }",
+            inputs,
+            BulkPollOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(results.len(), 3);
+
+    assert_eq!(results[0].input.id, "gid://shopify/Product/1");
+    assert!(!results[0].is_ok());
+    assert_eq!(results[0].user_errors[0].message, "Product not found");
+
+    // The line of the second input is missing from the result file
+    assert_eq!(results[1].input.id, "gid://shopify/Product/2");
+    assert!(results[1].data.is_none());
+    assert!(!results[1].is_ok());
+
+    assert_eq!(results[2].input.id, "gid://shopify/Product/3");
+    assert!(results[2].is_ok());
+    assert_eq!(
+        results[2].data.as_ref().unwrap().node.as_ref().unwrap()["id"],
+        "gid://shopify/Product/3"
+    );
+}
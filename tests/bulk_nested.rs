@@ -0,0 +1,71 @@
+use futures::{stream, TryStreamExt};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use shopify_api::graphql::bulk_nested::{regroup_bulk, regroup_bulk_stream, BulkParent};
+use shopify_api::{Gid, ShopifyAPIError};
+
+#[derive(Deserialize)]
+struct Product {
+    id: Gid,
+    #[serde(skip)]
+    variants: Vec<Gid>,
+    #[serde(skip)]
+    media: Vec<(String, Gid)>,
+}
+
+#[derive(Deserialize)]
+struct Node {
+    id: Gid,
+}
+
+impl BulkParent for Product {
+    type Child = Node;
+
+    fn add_child(&mut self, child: Node) {
+        self.variants.push(child.id);
+    }
+
+    fn add_nested_child(&mut self, parent_id: &str, child: Node) {
+        self.media.push((parent_id.to_string(), child.id));
+    }
+}
+
+fn records() -> Vec<Value> {
+    vec![
+        json!({ "id": "gid://shopify/Product/1" }),
+        json!({ "id": "gid://shopify/ProductVariant/2", "__parentId": "gid://shopify/Product/1" }),
+        json!({ "id": "gid://shopify/MediaImage/3", "__parentId": "gid://shopify/ProductVariant/2" }),
+        json!({ "id": "gid://shopify/Product/4" }),
+        json!({ "id": "gid://shopify/ProductVariant/5", "__parentId": "gid://shopify/Product/4" }),
+    ]
+}
+
+fn assert_regrouped(products: &[Product]) {
+    assert_eq!(products.len(), 2);
+    assert_eq!(products[0].id.to_string(), "gid://shopify/Product/1");
+    assert_eq!(products[0].variants.len(), 1);
+    assert_eq!(products[0].media.len(), 1);
+    assert_eq!(products[0].media[0].0, "gid://shopify/ProductVariant/2");
+    assert_eq!(products[1].variants.len(), 1);
+    assert!(products[1].media.is_empty());
+}
+
+#[test]
+fn grandchildren_are_given_to_their_top_level_record() {
+    let mut records = records();
+    // A grandchild read before its parent
+    records.swap(1, 2);
+
+    let products: Vec<Product> = regroup_bulk(records).unwrap();
+
+    assert_regrouped(&products);
+}
+
+#[tokio::test]
+async fn streamed_grandchildren_are_given_to_their_top_level_record() {
+    let records = stream::iter(records().into_iter().map(Ok::<_, ShopifyAPIError>));
+
+    let products: Vec<Product> = regroup_bulk_stream(records).try_collect().await.unwrap();
+
+    assert_regrouped(&products);
+}
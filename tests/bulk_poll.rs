@@ -0,0 +1,151 @@
+mod common;
+
+use futures::TryStreamExt;
+use serde_json::{json, Value};
+use shopify_api::graphql::bulk_poll::BulkPollOptions;
+use shopify_api::graphql::bulk_query::{ShopifyBulkErrorCode, ShopifyBulkStatus};
+use shopify_api::{Gid, ShopifyAPIError};
+use std::time::Duration;
+use wiremock::matchers::body_string_contains;
+use wiremock::MockServer;
+
+fn id() -> Gid {
+    "gid://shopify/BulkOperation/1".parse().unwrap()
+}
+
+fn options() -> BulkPollOptions {
+    BulkPollOptions::default()
+        .with_interval(Duration::from_millis(10))
+        .with_max_interval(Duration::from_millis(20))
+        .with_deadline(Some(Duration::from_secs(5)))
+}
+
+/// Answer the polls with the given snapshots, the last one is repeated
+async fn mount_snapshots(server: &MockServer, snapshots: Vec<Value>) {
+    let last = snapshots.len() - 1;
+
+    for (index, snapshot) in snapshots.into_iter().enumerate() {
+        let mut node = json!({ "id": "gid://shopify/BulkOperation/1" });
+        node.as_object_mut()
+            .unwrap()
+            .extend(snapshot.as_object().unwrap().clone());
+
+        let mock = common::graphql()
+            .and(body_string_contains("node(id"))
+            .respond_with(common::graphql_data(json!({ "node": node })));
+
+        match index == last {
+            true => mock.mount(server).await,
+            false => mock.up_to_n_times(1).mount(server).await,
+        }
+    }
+}
+
+#[tokio::test]
+async fn polls_until_the_operation_ends() {
+    let server = MockServer::start().await;
+    mount_snapshots(
+        &server,
+        vec![
+            json!({ "status": "CREATED" }),
+            json!({ "status": "RUNNING", "objectCount": "10" }),
+            json!({ "status": "COMPLETED", "objectCount": "25", "url": "https://storage/bulk.jsonl" }),
+        ],
+    )
+    .await;
+    let shopify = common::shopify(&server);
+    let id = id();
+
+    let snapshots: Vec<_> = shopify
+        .poll_bulk(&id, options())
+        .try_collect()
+        .await
+        .unwrap();
+
+    let statuses: Vec<_> = snapshots.iter().map(|bulk| bulk.status).collect();
+    assert_eq!(
+        statuses,
+        [
+            ShopifyBulkStatus::Created,
+            ShopifyBulkStatus::Running,
+            ShopifyBulkStatus::Completed
+        ]
+    );
+    assert_eq!(snapshots[2].object_count, Some(25.into()));
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 3);
+}
+
+#[tokio::test]
+async fn failed_operations_are_errors() {
+    let server = MockServer::start().await;
+    mount_snapshots(
+        &server,
+        vec![
+            json!({ "status": "RUNNING" }),
+            json!({ "status": "FAILED", "errorCode": "TIMEOUT", "partialDataUrl": "https://storage/partial.jsonl" }),
+        ],
+    )
+    .await;
+
+    let error = common::shopify(&server)
+        .wait_for_bulk_with(&id(), options(), |_| {})
+        .await
+        .unwrap_err();
+
+    match error {
+        ShopifyAPIError::BulkFailed {
+            id,
+            error_code,
+            partial_data_url,
+        } => {
+            assert_eq!(id, self::id());
+            assert_eq!(error_code, Some(ShopifyBulkErrorCode::Timeout));
+            assert_eq!(
+                partial_data_url.as_deref(),
+                Some("https://storage/partial.jsonl")
+            );
+        }
+        other => panic!("unexpected error {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn expired_operations_are_errors() {
+    let server = MockServer::start().await;
+    mount_snapshots(
+        &server,
+        vec![
+            json!({ "status": "RUNNING" }),
+            json!({ "status": "EXPIRED" }),
+        ],
+    )
+    .await;
+
+    let error = common::shopify(&server)
+        .wait_for_bulk_with(&id(), options(), |_| {})
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, ShopifyAPIError::BulkExpired(id) if id == self::id()));
+}
+
+#[tokio::test]
+async fn stops_at_the_deadline() {
+    let server = MockServer::start().await;
+    mount_snapshots(&server, vec![json!({ "status": "RUNNING" })]).await;
+    let mut progress = 0;
+
+    let error = common::shopify(&server)
+        .wait_for_bulk_with(
+            &id(),
+            options().with_deadline(Some(Duration::from_millis(100))),
+            |_| progress += 1,
+        )
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, ShopifyAPIError::BulkTimedOut(id) if id == self::id()));
+    assert!(progress >= 2);
+}
@@ -0,0 +1,63 @@
+#![allow(dead_code)]
+
+use serde_json::{json, Value};
+use shopify_api::retry::RetryPolicy;
+use shopify_api::Shopify;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockBuilder, MockServer, ResponseTemplate};
+
+pub const GRAPHQL_PATH: &str = "/admin/api/2024-04/graphql.json";
+
+/// A client sending its requests to the mock server
+pub fn shopify(server: &MockServer) -> Shopify {
+    Shopify::builder("myshop", "myapikey", "2024-04")
+        .base_url(&server.uri())
+        .build()
+        .unwrap()
+}
+
+/// A client sending its requests to the mock server, without retrying failed requests
+pub fn shopify_without_retry(server: &MockServer) -> Shopify {
+    Shopify::builder("myshop", "myapikey", "2024-04")
+        .base_url(&server.uri())
+        .retry_policy(RetryPolicy::none())
+        .build()
+        .unwrap()
+}
+
+/// A mock of the GraphQL endpoint
+pub fn graphql() -> MockBuilder {
+    Mock::given(method("POST")).and(path(GRAPHQL_PATH))
+}
+
+/// A GraphQL response with `data`
+pub fn graphql_data(data: Value) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(json!({ "data": data }))
+}
+
+/// The webhook secret of the test clients
+#[cfg(feature = "webhooks")]
+pub const SECRET: &str = "hush";
+
+/// The `X-Shopify-Hmac-Sha256` header of a webhook body signed with [`SECRET`]
+#[cfg(feature = "webhooks")]
+pub fn sign(body: &[u8]) -> String {
+    use base64::prelude::*;
+    use hmac::{Hmac, Mac};
+
+    let mut mac = Hmac::<sha2::Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+    mac.update(body);
+    BASE64_STANDARD.encode(mac.finalize().into_bytes())
+}
+
+/// A webhook request signed with [`SECRET`]
+#[cfg(feature = "webhooks")]
+pub fn signed_request(topic: &str, body: &[u8]) -> shopify_api::webhooks::handler::WebhookRequest {
+    shopify_api::webhooks::handler::WebhookRequest::new(
+        vec![
+            ("X-Shopify-Topic", topic),
+            ("X-Shopify-Hmac-Sha256", &sign(body)),
+        ],
+        body.to_vec(),
+    )
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use serde_json::json;
+use shopify_api::utils::ReadJsonTreeSteps;
+use shopify_api::ShopifyAPIError;
+use wiremock::MockServer;
+
+const MUTATION: &str = r#"mutation($input: ProductInput!) {
+    productUpdate(input: $input) {
+        product { id }
+        userErrors { field message code }
+    }
+}"#;
+
+fn payload_path() -> Vec<ReadJsonTreeSteps<'static>> {
+    vec![
+        ReadJsonTreeSteps::Key("data"),
+        ReadJsonTreeSteps::Key("productUpdate"),
+    ]
+}
+
+#[tokio::test]
+async fn user_errors_are_returned_as_an_error() {
+    let server = MockServer::start().await;
+    common::graphql()
+        .respond_with(common::graphql_data(json!({
+            "productUpdate": {
+                "product": null,
+                "userErrors": [
+                    { "field": ["input", "variants", "0", "price"], "message": "Price is invalid", "code": "INVALID" },
+                    { "field": null, "message": "Product is locked", "code": null },
+                ],
+            },
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let error = common::shopify(&server)
+        .graphql_mutation::<serde_json::Value, _>(
+            MUTATION,
+            &json!({ "input": { "id": "gid://shopify/Product/1" } }),
+            &payload_path(),
+        )
+        .await
+        .unwrap_err();
+
+    let user_errors = match error {
+        ShopifyAPIError::UserErrors(user_errors) => user_errors,
+        error => panic!("Unexpected error: {:?}", error),
+    };
+    assert_eq!(user_errors.len(), 2);
+    assert_eq!(
+        user_errors[0].field.as_deref(),
+        Some(&["input", "variants", "0", "price"].map(String::from)[..])
+    );
+    assert_eq!(user_errors[0].code.as_deref(), Some("INVALID"));
+    assert_eq!(
+        user_errors[0].to_string(),
+        "input.variants.0.price: Price is invalid (INVALID)"
+    );
+    assert_eq!(user_errors[1].field, None);
+    assert_eq!(user_errors[1].message, "Product is locked");
+}
+
+#[tokio::test]
+async fn empty_user_errors_return_the_payload() {
+    let server = MockServer::start().await;
+    common::graphql()
+        .respond_with(common::graphql_data(json!({
+            "productUpdate": {
+                "product": { "id": "gid://shopify/Product/1" },
+                "userErrors": [],
+            },
+        })))
+        .mount(&server)
+        .await;
+
+    let payload: serde_json::Value = common::shopify(&server)
+        .graphql_mutation(
+            MUTATION,
+            &json!({ "input": { "id": "gid://shopify/Product/1" } }),
+            &payload_path(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(payload["product"]["id"], "gid://shopify/Product/1");
+}
+
+#[tokio::test]
+async fn mutations_after_a_fragment_are_not_retried() {
+    let server = MockServer::start().await;
+    common::graphql()
+        .respond_with(wiremock::ResponseTemplate::new(503))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let error = common::shopify(&server)
+        .graphql_query::<serde_json::Value, _>(
+            r#"
+            # Create a product
+            fragment ProductFields on Product { id title }
+
+            mutation($input: ProductInput!) {
+                productCreate(input: $input) {
+                    product { ...ProductFields }
+                    userErrors { field message }
+                }
+            }"#,
+            &json!({ "input": { "title": "Hat" } }),
+            &payload_path(),
+        )
+        .await
+        .unwrap_err();
+
+    assert!(error.status() == Some(reqwest::StatusCode::SERVICE_UNAVAILABLE));
+    assert_eq!(server.received_requests().await.unwrap().len(), 1);
+}
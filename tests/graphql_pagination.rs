@@ -0,0 +1,86 @@
+mod common;
+
+use futures::TryStreamExt;
+use serde_json::{json, Value};
+use shopify_api::utils::ReadJsonTreeSteps;
+use shopify_api::ShopifyAPIError;
+use wiremock::matchers::body_partial_json;
+use wiremock::MockServer;
+
+const QUERY: &str = r#"query($after: String, $query: String) {
+    products(first: 2, after: $after, query: $query) {
+        nodes { id }
+        pageInfo { hasNextPage endCursor }
+    }
+}"#;
+
+fn connection_path() -> Vec<ReadJsonTreeSteps<'static>> {
+    vec![
+        ReadJsonTreeSteps::Key("data"),
+        ReadJsonTreeSteps::Key("products"),
+    ]
+}
+
+#[tokio::test]
+async fn follows_the_cursor_until_the_last_page() {
+    let server = MockServer::start().await;
+    common::graphql()
+        .and(body_partial_json(
+            json!({ "variables": { "after": null, "query": "tag:sale" } }),
+        ))
+        .respond_with(common::graphql_data(json!({
+            "products": {
+                "nodes": [{ "id": 1 }, { "id": 2 }],
+                "pageInfo": { "hasNextPage": true, "endCursor": "cursor-2" },
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    common::graphql()
+        .and(body_partial_json(
+            json!({ "variables": { "after": "cursor-2", "query": "tag:sale" } }),
+        ))
+        .respond_with(common::graphql_data(json!({
+            "products": {
+                "edges": [{ "node": { "id": 3 } }],
+                "pageInfo": { "hasNextPage": false, "endCursor": "cursor-3" },
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let shopify = common::shopify(&server);
+
+    let products: Vec<Value> = shopify
+        .graphql_paginate(
+            QUERY,
+            &json!({ "query": "tag:sale" }),
+            connection_path(),
+            None,
+        )
+        .try_collect()
+        .await
+        .unwrap();
+
+    let ids: Vec<_> = products
+        .iter()
+        .map(|product| product["id"].clone())
+        .collect();
+    assert_eq!(ids, [1, 2, 3]);
+    assert_eq!(server.received_requests().await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn variables_which_are_not_an_object_are_an_error() {
+    let server = MockServer::start().await;
+    let shopify = common::shopify(&server);
+
+    let result: Result<Vec<Value>, ShopifyAPIError> = shopify
+        .graphql_paginate(QUERY, &json!(["tag:sale"]), connection_path(), None)
+        .try_collect()
+        .await;
+
+    assert!(matches!(result, Err(ShopifyAPIError::Other(_))));
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
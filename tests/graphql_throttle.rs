@@ -0,0 +1,96 @@
+mod common;
+
+use serde_json::{json, Value};
+use shopify_api::rate_limit::{
+    GraphQLCostLimiter, ShopifyGraphQLCost, ShopifyGraphQLThrottleStatus,
+};
+use shopify_api::retry::RetryPolicy;
+use shopify_api::utils::ReadJsonTreeSteps;
+use shopify_api::Shopify;
+use std::time::{Duration, Instant};
+use wiremock::{MockServer, ResponseTemplate};
+
+fn cost(requested: f64, maximum: f64, available: f64, restore_rate: f64) -> Value {
+    json!({
+        "requestedQueryCost": requested,
+        "actualQueryCost": null,
+        "throttleStatus": {
+            "maximumAvailable": maximum,
+            "currentlyAvailable": available,
+            "restoreRate": restore_rate,
+        },
+    })
+}
+
+#[tokio::test]
+async fn throttled_queries_wait_for_the_bucket_and_are_retried() {
+    let server = MockServer::start().await;
+    // The bucket is empty and restores 1000 points per second: the 100 points query must wait 100ms
+    common::graphql()
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "errors": [{ "message": "Throttled", "extensions": { "code": "THROTTLED" } }],
+            "extensions": { "cost": cost(100.0, 1000.0, 0.0, 1000.0) },
+        })))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    common::graphql()
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "shop": { "name": "myshop" } },
+            "extensions": { "cost": cost(100.0, 2000.0, 1900.0, 100.0) },
+        })))
+        .mount(&server)
+        .await;
+
+    let shopify = Shopify::builder("myshop", "myapikey", "2024-04")
+        .base_url(&server.uri())
+        .retry_policy(
+            RetryPolicy::default()
+                .with_base_delay(Duration::from_millis(1))
+                .with_jitter(false),
+        )
+        .build()
+        .unwrap();
+    assert!(shopify.graphql_throttle_status().is_none());
+
+    let started_at = Instant::now();
+    let name: String = shopify
+        .graphql_query(
+            "query { shop { name } }",
+            &json!({}),
+            &vec![
+                ReadJsonTreeSteps::Key("data"),
+                ReadJsonTreeSteps::Key("shop"),
+                ReadJsonTreeSteps::Key("name"),
+            ],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(name, "myshop");
+    assert_eq!(server.received_requests().await.unwrap().len(), 2);
+    assert!(started_at.elapsed() >= Duration::from_millis(90));
+
+    let status = shopify.graphql_throttle_status().unwrap();
+    assert_eq!(status.maximum_available, 2000.0);
+    assert!(status.currently_available >= 1900.0);
+}
+
+#[tokio::test]
+async fn tiny_restore_rates_do_not_panic() {
+    let limiter = GraphQLCostLimiter::new();
+    limiter.update(&ShopifyGraphQLCost {
+        requested_query_cost: 100.0,
+        actual_query_cost: None,
+        throttle_status: ShopifyGraphQLThrottleStatus {
+            maximum_available: 1000.0,
+            currently_available: 0.0,
+            restore_rate: f64::MIN_POSITIVE,
+        },
+    });
+
+    // The wait is capped: acquiring waits instead of overflowing the delay
+    let acquired = tokio::time::timeout(Duration::from_millis(50), limiter.acquire()).await;
+    assert!(acquired.is_err());
+    assert!(limiter.throttle_status().is_some());
+}
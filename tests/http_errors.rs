@@ -0,0 +1,113 @@
+mod common;
+
+use serde_json::json;
+use shopify_api::rest::ShopifyAPIRestType;
+use shopify_api::utils::ReadJsonTreeSteps;
+use shopify_api::ShopifyAPIError;
+use std::collections::HashMap;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn error_response(status: u16) -> ResponseTemplate {
+    ResponseTemplate::new(status)
+        .insert_header("X-Request-Id", format!("request-{}", status).as_str())
+        .set_body_string(format!("error {}", status))
+}
+
+fn assert_maps_status(status: u16, error: &ShopifyAPIError) {
+    let request_id = format!("request-{}", status);
+    let body = format!("error {}", status);
+
+    match (status, error) {
+        (401, ShopifyAPIError::Unauthorized { body: b, .. })
+        | (403, ShopifyAPIError::Forbidden { body: b, .. })
+        | (404, ShopifyAPIError::NotFound { body: b, .. })
+        | (429, ShopifyAPIError::Throttled { body: b, .. }) => assert_eq!(b, &body),
+        (
+            500 | 503,
+            ShopifyAPIError::HttpError {
+                status: s, body: b, ..
+            },
+        ) => {
+            assert_eq!(s.as_u16(), status);
+            assert_eq!(b, &body);
+        }
+        _ => panic!("{} was mapped to {:?}", status, error),
+    }
+
+    assert_eq!(error.status().map(|s| s.as_u16()), Some(status));
+    assert_eq!(error.request_id(), Some(request_id.as_str()));
+}
+
+#[tokio::test]
+async fn rest_statuses_are_mapped_to_errors() {
+    for status in [401, 403, 404, 429, 500, 503] {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/admin/api/2024-04/shop.json"))
+            .respond_with(error_response(status))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let error = common::shopify_without_retry(&server)
+            .rest_query::<serde_json::Value>(
+                &ShopifyAPIRestType::Get("shop.json", &HashMap::new()),
+                &None,
+            )
+            .await
+            .unwrap_err();
+
+        assert_maps_status(status, &error);
+    }
+}
+
+#[tokio::test]
+async fn graphql_statuses_are_mapped_to_errors() {
+    for status in [401, 403, 404, 429, 500, 503] {
+        let server = MockServer::start().await;
+        common::graphql()
+            .respond_with(error_response(status))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let error = common::shopify_without_retry(&server)
+            .graphql_query::<serde_json::Value, _>(
+                "query { shop { name } }",
+                &json!({}),
+                &vec![ReadJsonTreeSteps::Key("data")],
+            )
+            .await
+            .unwrap_err();
+
+        assert_maps_status(status, &error);
+    }
+}
+
+#[tokio::test]
+async fn graphql_errors_of_an_unexpected_shape_are_not_dropped() {
+    let server = MockServer::start().await;
+    common::graphql()
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "shop": { "name": "myshop" } },
+            "errors": { "unexpected": "shape" },
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let error = common::shopify_without_retry(&server)
+        .graphql_query::<serde_json::Value, _>(
+            "query { shop { name } }",
+            &json!({}),
+            &vec![ReadJsonTreeSteps::Key("data")],
+        )
+        .await
+        .unwrap_err();
+
+    match error {
+        ShopifyAPIError::NotWantedJsonFormat(errors) => assert!(errors.contains("unexpected")),
+        error => panic!("Unexpected error: {:?}", error),
+    }
+}
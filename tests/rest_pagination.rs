@@ -0,0 +1,56 @@
+use futures::StreamExt;
+use serde_json::{json, Value};
+use shopify_api::utils::ReadJsonTreeSteps;
+use shopify_api::{Shopify, ShopifyAPIError};
+use std::collections::HashMap;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn cross_origin_next_links_are_not_followed() {
+    let server = MockServer::start().await;
+    let elsewhere = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/admin/api/2024-04/products.json"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header(
+                    "Link",
+                    format!(
+                        r#"<{}/admin/api/2024-04/products.json?page_info=abc>; rel="next""#,
+                        elsewhere.uri()
+                    )
+                    .as_str(),
+                )
+                .set_body_json(json!({ "products": [{ "id": 1 }] })),
+        )
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(header("X-Shopify-Access-Token", "myapikey"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "products": [] })))
+        .expect(0)
+        .mount(&elsewhere)
+        .await;
+
+    let shopify = Shopify::builder("myshop", "myapikey", "2024-04")
+        .base_url(&server.uri())
+        .build()
+        .unwrap();
+    let params = HashMap::new();
+
+    let results: Vec<Result<Value, ShopifyAPIError>> = shopify
+        .rest_paginate(
+            "products.json",
+            &params,
+            vec![ReadJsonTreeSteps::Key("products")],
+        )
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].as_ref().unwrap()["id"], 1);
+    assert!(matches!(results[1], Err(ShopifyAPIError::Other(_))));
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use serde_json::json;
+use shopify_api::rate_limit::{RestCallLimiter, ShopifyRestCallLimit};
+use shopify_api::rest::ShopifyAPIRestType;
+use shopify_api::retry::RetryPolicy;
+use shopify_api::utils::ReadJsonTreeSteps;
+use shopify_api::Shopify;
+use std::collections::HashMap;
+use std::time::Duration;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn throttled_requests_are_retried_and_the_call_limit_is_read() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/admin/api/2024-04/shop.json"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/admin/api/2024-04/shop.json"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("X-Shopify-Shop-Api-Call-Limit", "12/80")
+                .set_body_json(json!({ "shop": { "name": "myshop" } })),
+        )
+        .mount(&server)
+        .await;
+
+    let shopify = Shopify::builder("myshop", "myapikey", "2024-04")
+        .base_url(&server.uri())
+        .rest_limiter(RestCallLimiter::new(100.0))
+        .retry_policy(
+            RetryPolicy::default()
+                .with_base_delay(Duration::from_millis(1))
+                .with_jitter(false),
+        )
+        .build()
+        .unwrap();
+    assert_eq!(shopify.rest_call_limit().capacity, 40);
+
+    let name: String = shopify
+        .rest_query(
+            &ShopifyAPIRestType::Get("shop.json", &HashMap::new()),
+            &Some(vec![
+                ReadJsonTreeSteps::Key("shop"),
+                ReadJsonTreeSteps::Key("name"),
+            ]),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(name, "myshop");
+    assert_eq!(server.received_requests().await.unwrap().len(), 2);
+
+    let limit = shopify.rest_call_limit();
+    assert_eq!(limit.capacity, 80);
+    assert!(limit.used <= 12);
+}
+
+#[tokio::test]
+async fn unbounded_retry_after_headers_do_not_panic() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/admin/api/2024-04/shop.json"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "inf"))
+        .mount(&server)
+        .await;
+
+    let error = common::shopify_without_retry(&server)
+        .rest_query::<serde_json::Value>(
+            &ShopifyAPIRestType::Get("shop.json", &HashMap::new()),
+            &None,
+        )
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        error,
+        shopify_api::ShopifyAPIError::Throttled { .. }
+    ));
+}
+
+#[tokio::test]
+async fn tiny_leak_rates_do_not_panic() {
+    let limiter = RestCallLimiter::new(f64::MIN_POSITIVE);
+    limiter.update(&ShopifyRestCallLimit {
+        used: 40,
+        capacity: 40,
+    });
+
+    // The wait is capped: acquiring waits instead of overflowing the delay
+    let acquired = tokio::time::timeout(Duration::from_millis(50), limiter.acquire()).await;
+    assert!(acquired.is_err());
+    assert_eq!(limiter.call_limit().capacity, 40);
+}
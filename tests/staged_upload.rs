@@ -0,0 +1,122 @@
+mod common;
+
+use serde_json::json;
+use shopify_api::graphql::bulk_query::{
+    StagedUploadTargetGenerateUploadResourceInput, StagedUploadsCreateInput,
+};
+use shopify_api::graphql::types::UnsignedInt64;
+use wiremock::matchers::{body_string_contains, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn mount_target(server: &MockServer, parameters: serde_json::Value) {
+    common::graphql()
+        .and(body_string_contains("stagedUploadsCreate"))
+        .respond_with(common::graphql_data(json!({
+            "stagedUploadsCreate": {
+                "stagedTargets": [{
+                    "url": format!("{}/upload", server.uri()),
+                    "resourceUrl": format!("{}/upload/demo", server.uri()),
+                    "parameters": parameters,
+                }],
+                "userErrors": [],
+            }
+        })))
+        .expect(1)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn signed_forms_are_posted_as_multipart() {
+    let server = MockServer::start().await;
+    mount_target(
+        &server,
+        json!([
+            { "name": "GoogleAccessId", "value": "uploads@shopify" },
+            { "name": "key", "value": "tmp/demo.mp4" },
+            { "name": "policy", "value": "c2lnbmVk" },
+            { "name": "signature", "value": "abc123" },
+        ]),
+    )
+    .await;
+    Mock::given(method("POST"))
+        .and(path("/upload"))
+        .respond_with(ResponseTemplate::new(201))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let input = StagedUploadsCreateInput {
+        file_size: Some(UnsignedInt64(11)),
+        filename: "demo.mp4".to_string(),
+        http_method: None,
+        mime_type: "video/mp4".to_string(),
+        resource: StagedUploadTargetGenerateUploadResourceInput::Video,
+    };
+    let upload = common::shopify(&server)
+        .stage_upload(input, std::io::Cursor::new(b"video bytes".to_vec()))
+        .await
+        .unwrap();
+
+    assert_eq!(upload.key.as_deref(), Some("tmp/demo.mp4"));
+
+    let requests = server.received_requests().await.unwrap();
+    let graphql = String::from_utf8_lossy(&requests[0].body);
+    assert!(!graphql.contains("httpMethod"));
+
+    let upload = &requests[1];
+    let content_type = upload.headers["content-type"].to_str().unwrap();
+    assert!(content_type.starts_with("multipart/form-data"));
+    assert!(!upload.headers.contains_key("policy"));
+
+    let body = String::from_utf8_lossy(&upload.body);
+    assert!(body.contains("name=\"policy\"\r\n\r\nc2lnbmVk"));
+    assert!(body.contains("name=\"GoogleAccessId\"\r\n\r\nuploads@shopify"));
+    assert!(body.contains("filename=\"demo.mp4\""));
+    assert!(body.contains("video bytes"));
+}
+
+#[tokio::test]
+async fn unsigned_targets_are_put_with_their_headers() {
+    let server = MockServer::start().await;
+    mount_target(
+        &server,
+        json!([
+            { "name": "content_type", "value": "image/png" },
+            { "name": "acl", "value": "private" },
+            { "name": "x-unexpected", "value": "ignored" },
+        ]),
+    )
+    .await;
+    Mock::given(method("PUT"))
+        .and(path("/upload"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let file = std::env::temp_dir().join(format!("shopify_api_cover_{}.png", std::process::id()));
+    std::fs::write(&file, b"png bytes").unwrap();
+
+    let upload = common::shopify(&server)
+        .stage_upload_file(
+            &file,
+            "image/png",
+            StagedUploadTargetGenerateUploadResourceInput::Image,
+        )
+        .await;
+    std::fs::remove_file(&file).unwrap();
+
+    assert_eq!(
+        upload.unwrap().resource_url,
+        Some(format!("{}/upload/demo", server.uri()))
+    );
+
+    let requests = server.received_requests().await.unwrap();
+    let upload = &requests[1];
+    assert_eq!(upload.headers["content-type"], "image/png");
+    assert_eq!(upload.headers["x-goog-acl"], "private");
+    assert_eq!(upload.headers["content-length"], "9");
+    assert!(!upload.headers.contains_key("x-unexpected"));
+    assert_eq!(upload.body, b"png bytes");
+}
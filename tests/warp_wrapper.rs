@@ -0,0 +1,43 @@
+#![cfg(all(feature = "webhooks", feature = "warp-wrapper"))]
+
+mod common;
+
+use common::{sign, SECRET};
+use shopify_api::Shopify;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use warp::http::StatusCode;
+
+async fn status(fail: bool, hmac: &str) -> StatusCode {
+    let shopify = Shopify::new("myshop", "myapikey", String::from("2024-04"), Some(SECRET));
+    let filter = Shopify::warp_wrapper(
+        "webhooks",
+        Arc::new(Mutex::new(shopify)),
+        (),
+        move |_event, _shopify, _extra| async move {
+            match fail {
+                true => Err("callback failed".to_string()),
+                false => Ok(()),
+            }
+        },
+    );
+
+    warp::test::request()
+        .method("POST")
+        .path("/webhooks")
+        .header("X-Shopify-Topic", "products/delete")
+        .header("X-Shopify-Hmac-Sha256", hmac)
+        .body(r#"{"id": 788032119674292922}"#)
+        .reply(&filter)
+        .await
+        .status()
+}
+
+#[tokio::test]
+async fn answers_with_the_outcome_status() {
+    let hmac = sign(br#"{"id": 788032119674292922}"#);
+
+    assert_eq!(status(false, &hmac).await, StatusCode::OK);
+    assert_eq!(status(false, "forged").await, StatusCode::UNAUTHORIZED);
+    assert_eq!(status(true, &hmac).await, StatusCode::INTERNAL_SERVER_ERROR);
+}
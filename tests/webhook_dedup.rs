@@ -0,0 +1,37 @@
+#![cfg(feature = "webhooks")]
+
+use shopify_api::webhooks::dedup::{MemoryDedupStore, WebhookClaim, WebhookDedupStore};
+use std::time::Duration;
+
+#[tokio::test]
+async fn abandoned_claims_expire_after_the_claim_ttl() {
+    let store = MemoryDedupStore::default().with_claim_ttl(Duration::from_millis(50));
+
+    assert_eq!(store.claim("a").await.unwrap(), WebhookClaim::Claimed);
+    assert_eq!(store.claim("a").await.unwrap(), WebhookClaim::InProgress);
+
+    // Well before the next pruning, the expired claim is still taken over
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert_eq!(store.claim("a").await.unwrap(), WebhookClaim::Claimed);
+}
+
+#[tokio::test]
+async fn processed_deliveries_expire_after_the_ttl() {
+    let store = MemoryDedupStore::new(Duration::from_millis(50));
+
+    assert_eq!(store.claim("a").await.unwrap(), WebhookClaim::Claimed);
+    store.complete("a").await.unwrap();
+    assert_eq!(store.claim("a").await.unwrap(), WebhookClaim::Processed);
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert_eq!(store.claim("a").await.unwrap(), WebhookClaim::Claimed);
+}
+
+#[tokio::test]
+async fn released_deliveries_are_claimed_again() {
+    let store = MemoryDedupStore::default();
+
+    assert_eq!(store.claim("a").await.unwrap(), WebhookClaim::Claimed);
+    store.release("a").await.unwrap();
+    assert_eq!(store.claim("a").await.unwrap(), WebhookClaim::Claimed);
+}
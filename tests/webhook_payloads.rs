@@ -0,0 +1,241 @@
+#![cfg(feature = "webhooks")]
+
+mod common;
+
+use common::{signed_request, SECRET};
+use rust_decimal::Decimal;
+use shopify_api::webhooks::frameworks::ShopifyWebhook;
+use shopify_api::webhooks::handler::WebhookError;
+use shopify_api::webhooks::topic::WebhookTopic;
+use shopify_api::Shopify;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+fn decimal(value: &str) -> Decimal {
+    Decimal::from_str(value).unwrap()
+}
+
+#[test]
+fn orders_updated_keeps_refunds_and_fulfillments() {
+    let body = include_bytes!("fixtures/orders_updated.json");
+
+    let order = match ShopifyWebhook::parse(&WebhookTopic::OrdersUpdated, body).unwrap() {
+        ShopifyWebhook::OrdersUpdated(order) => order,
+        other => panic!("unexpected webhook {:?}", other),
+    };
+
+    let client_details = order.client_details.unwrap();
+    assert_eq!(client_details.browser_width, Some(1280));
+    assert_eq!(
+        order.current_total_duties_set.unwrap().shop_money.amount,
+        decimal("4.75")
+    );
+
+    let discount = &order.discount_applications[0];
+    assert_eq!(discount._type, "discount_code");
+    assert_eq!(discount.code.as_deref(), Some("WELCOME20"));
+    assert_eq!(discount.value, decimal("20.0"));
+    let allocation = &order.line_items[0].discount_allocations[0];
+    assert_eq!(allocation.amount, decimal("10.00"));
+    assert_eq!(allocation.discount_application_index, 0);
+
+    let fulfillment = &order.fulfillments[0];
+    assert_eq!(fulfillment.status, "success");
+    assert_eq!(fulfillment.tracking_company.as_deref(), Some("UPS"));
+    assert_eq!(fulfillment.tracking_numbers, vec!["1Z2345"]);
+    assert_eq!(fulfillment.receipt.as_ref().unwrap().testcase, Some(true));
+    assert_eq!(fulfillment.line_items[0].id, 866550311766439020);
+
+    let payment_terms = order.payment_terms.unwrap();
+    assert_eq!(payment_terms.payment_terms_type, "net");
+    assert_eq!(payment_terms.due_in_days, Some(30));
+    assert_eq!(payment_terms.payment_schedules[0].amount, decimal("398.00"));
+
+    let refund = &order.refunds[0];
+    assert_eq!(refund.note.as_deref(), Some("it broke during shipping"));
+    assert_eq!(refund.refund_line_items[0].line_item_id, 141249953214522974);
+    assert_eq!(refund.refund_line_items[0].restock_type, "return");
+    let transaction = &refund.transactions[0];
+    assert_eq!(transaction.kind, "refund");
+    assert_eq!(transaction.amount, decimal("209.00"));
+    assert_eq!(transaction.parent_id, Some(801038806));
+}
+
+#[test]
+fn custom_line_items_have_no_product() {
+    let body = include_bytes!("fixtures/orders_create_custom_item.json");
+
+    let order = match ShopifyWebhook::parse(&WebhookTopic::OrdersCreate, body).unwrap() {
+        ShopifyWebhook::OrdersCreate(order) => order,
+        other => panic!("unexpected webhook {:?}", other),
+    };
+
+    let line_item = &order.line_items[0];
+    assert_eq!(line_item.title, "Gift wrapping");
+    assert_eq!(line_item.product_id, None);
+    assert_eq!(line_item.variant_id, None);
+    assert!(!line_item.product_exists);
+}
+
+#[test]
+fn refunds_create_is_typed() {
+    let body = include_bytes!("fixtures/refunds_create.json");
+
+    let refund = match ShopifyWebhook::parse(&WebhookTopic::RefundsCreate, body).unwrap() {
+        ShopifyWebhook::RefundsCreate(refund) => refund,
+        other => panic!("unexpected webhook {:?}", other),
+    };
+
+    assert_eq!(refund.id, 890088186047892319);
+    assert_eq!(refund.order_adjustments[0].kind, "shipping_refund");
+    assert_eq!(refund.order_adjustments[0].amount, decimal("-5.00"));
+
+    let refund_line_item = &refund.refund_line_items[0];
+    assert_eq!(refund_line_item.subtotal, decimal("199.00"));
+    assert_eq!(
+        refund_line_item.line_item.as_ref().unwrap().title,
+        "Aviator sunglasses"
+    );
+}
+
+#[test]
+fn fulfillments_create_is_typed() {
+    let body = include_bytes!("fixtures/fulfillments_create.json");
+
+    let fulfillment = match ShopifyWebhook::parse(&WebhookTopic::FulfillmentsCreate, body).unwrap()
+    {
+        ShopifyWebhook::FulfillmentsCreate(fulfillment) => fulfillment,
+        other => panic!("unexpected webhook {:?}", other),
+    };
+
+    assert_eq!(fulfillment.order_id, 820982911946154508);
+    assert_eq!(fulfillment.tracking_number.as_deref(), Some("1z827wk74630"));
+    assert_eq!(fulfillment.tracking_urls.len(), 1);
+    assert_eq!(fulfillment.receipt.unwrap().authorization, None);
+    assert_eq!(
+        fulfillment.line_items[0].sku.as_deref(),
+        Some("SKU2006-001")
+    );
+}
+
+#[test]
+fn unexpected_payloads_are_reported() {
+    let reported = Arc::new(Mutex::new(vec![]));
+    let hook_reported = reported.clone();
+    let shopify = Shopify::builder("myshop", "myapikey", "2024-04")
+        .shared_secret(SECRET)
+        .webhook_error_hook(move |error| hook_reported.lock().unwrap().push(error.to_string()))
+        .build()
+        .unwrap();
+
+    let request = signed_request("products/delete", br#"{"id": "not a number"}"#);
+    let error = shopify.process_webhook(&request).unwrap_err();
+    assert!(matches!(error, WebhookError::InvalidPayload { .. }));
+    assert_eq!(error.status_code(), 400);
+
+    let request = signed_request("products/delete", b"not json");
+    assert!(shopify.process_webhook(&request).is_err());
+
+    assert_eq!(reported.lock().unwrap().len(), 2);
+    #[cfg(feature = "debug")]
+    assert!(reported.lock().unwrap()[0].contains("id"));
+}
+
+#[test]
+fn raw_fallback_delivers_unexpected_payloads() {
+    let reported = Arc::new(Mutex::new(0));
+    let hook_reported = reported.clone();
+    let shopify = Shopify::builder("myshop", "myapikey", "2024-04")
+        .shared_secret(SECRET)
+        .webhook_raw_fallback(true)
+        .webhook_error_hook(move |_| *hook_reported.lock().unwrap() += 1)
+        .build()
+        .unwrap();
+
+    let request = signed_request("products/delete", br#"{"id": "not a number"}"#);
+    let event = shopify.process_webhook(&request).unwrap();
+
+    assert!(matches!(
+        event.payload,
+        ShopifyWebhook::Other((topic, raw)) if topic == "products/delete" && raw["id"] == "not a number"
+    ));
+    assert_eq!(*reported.lock().unwrap(), 1);
+
+    let request = signed_request("products/delete", b"not json");
+    assert!(shopify.process_webhook(&request).is_err());
+}
+
+#[test]
+fn delivery_headers_are_exposed() {
+    let shopify = Shopify::new("myshop", "myapikey", String::from("2024-04"), Some(SECRET));
+    let request = signed_request("products/delete", br#"{"id": 1}"#)
+        .with_header(
+            "X-Shopify-Webhook-Id",
+            "b54557e4-bdd9-4b37-8a5f-bf7d70bcd043",
+        )
+        .with_header("X-Shopify-Event-Id", "98880550-7158-44d4-b7cd-2c97c8a091b5")
+        .with_header("X-Shopify-Triggered-At", "2023-03-29T18:00:27.877041743Z");
+
+    let event = shopify.process_webhook(&request).unwrap();
+
+    assert_eq!(
+        event.event_id.as_deref(),
+        Some("98880550-7158-44d4-b7cd-2c97c8a091b5")
+    );
+    assert_eq!(
+        event.dedup_key().as_deref(),
+        Some("b54557e4-bdd9-4b37-8a5f-bf7d70bcd043")
+    );
+    assert_eq!(
+        event.triggered_at.unwrap().to_rfc3339(),
+        "2023-03-29T18:00:27.877041743+00:00"
+    );
+}
+
+#[test]
+fn event_ids_are_deduplicated_per_topic() {
+    let shopify = Shopify::new("myshop", "myapikey", String::from("2024-04"), Some(SECRET));
+    let event_id = "98880550-7158-44d4-b7cd-2c97c8a091b5";
+
+    let product = shopify
+        .process_webhook(
+            &signed_request("products/delete", br#"{"id": 1}"#)
+                .with_header("X-Shopify-Event-Id", event_id),
+        )
+        .unwrap();
+    let collection = shopify
+        .process_webhook(
+            &signed_request("collections/delete", br#"{"id": 1}"#)
+                .with_header("X-Shopify-Event-Id", event_id),
+        )
+        .unwrap();
+
+    assert_eq!(
+        product.dedup_key().as_deref(),
+        Some("products/delete:98880550-7158-44d4-b7cd-2c97c8a091b5")
+    );
+    assert_ne!(product.dedup_key(), collection.dedup_key());
+}
+
+#[test]
+fn webhooks_signed_with_a_previous_secret_are_accepted() {
+    let shopify = Shopify::builder("myshop", "myapikey", "2024-04")
+        .shared_secrets(["rotated", SECRET])
+        .build()
+        .unwrap();
+
+    let event = shopify
+        .process_webhook(&signed_request("products/delete", br#"{"id": 1}"#))
+        .unwrap();
+    assert_eq!(event.secret_index, 1);
+
+    let shopify = Shopify::builder("myshop", "myapikey", "2024-04")
+        .shared_secrets(["rotated"])
+        .build()
+        .unwrap();
+
+    let error = shopify
+        .process_webhook(&signed_request("products/delete", br#"{"id": 1}"#))
+        .unwrap_err();
+    assert!(matches!(error, WebhookError::InvalidHmac));
+}